@@ -359,7 +359,11 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        self.deserialize_any(visitor)
+        // skip every value line of unknown field
+        while !self.peek_delimiter() {
+            self.parse_line()?;
+        }
+        visitor.visit_unit()
     }
 }
 
@@ -1021,4 +1025,31 @@ mingw-w64-x86_64-popt"#;
         };
         assert_eq!(expected, crate::from_str(j).unwrap());
     }
+
+    #[test]
+    fn test_unknown_fields_are_ignored() {
+        #[derive(Deserialize, PartialEq, Debug)]
+        struct Test {
+            #[serde(rename = "NAME")]
+            name: String,
+            #[serde(rename = "VERSION")]
+            version: String,
+        }
+
+        let j = r#"%NAME%
+pacman
+
+%XDATA%
+pkgtype=pkg
+other=value
+
+%VERSION%
+6.0.1-1
+"#;
+        let expected = Test {
+            name: "pacman".to_owned(),
+            version: "6.0.1-1".to_owned(),
+        };
+        assert_eq!(expected, crate::from_str(j).unwrap());
+    }
 }
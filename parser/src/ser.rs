@@ -89,7 +89,7 @@ impl<'a> ser::Serializer for &'a mut Serializer {
     }
 
     fn serialize_none(self) -> Result<()> {
-        self.serialize_unit()
+        Ok(())
     }
 
     fn serialize_some<T>(self, value: &T) -> Result<()>
@@ -201,7 +201,7 @@ impl<'a> ser::SerializeSeq for &'a mut Serializer {
     }
 
     fn end(self) -> Result<()> {
-        self.output = self.output[..self.output.len() - 1].to_owned(); // Cut last \n
+        self.output.pop(); // Cut last \n
         Ok(())
     }
 }
@@ -220,7 +220,7 @@ impl<'a> ser::SerializeTuple for &'a mut Serializer {
     }
 
     fn end(self) -> Result<()> {
-        self.output = self.output[..self.output.len() - 1].to_owned(); // Cut last \n
+        self.output.pop(); // Cut last \n
         Ok(())
     }
 }
@@ -239,7 +239,7 @@ impl<'a> ser::SerializeTupleStruct for &'a mut Serializer {
     }
 
     fn end(self) -> Result<()> {
-        self.output = self.output[..self.output.len() - 1].to_owned(); // Cut last \n
+        self.output.pop(); // Cut last \n
         Ok(())
     }
 }
@@ -298,11 +298,19 @@ impl<'a> ser::SerializeStruct for &'a mut Serializer {
     where
         T: ?Sized + Serialize,
     {
+        let mut value_serializer = Serializer {
+            output: String::new(),
+        };
+        value.serialize(&mut value_serializer)?;
+        // missing values and empty lists are omitted, like in files written by repo-add
+        if value_serializer.output.is_empty() {
+            return Ok(());
+        }
         self.output += "%";
         key.serialize(&mut **self)?;
         self.output += "%";
         self.output += "\n";
-        value.serialize(&mut **self)?;
+        self.output += &value_serializer.output;
         self.output += "\n";
         self.output += "\n";
         Ok(())
@@ -370,4 +378,27 @@ mingw-w64-x86_64-popt
         };
         assert_eq!(crate::to_string(&val).unwrap(), j);
     }
+
+    #[test]
+    fn test_missing_fields() {
+        #[derive(Serialize, PartialEq, Debug)]
+        struct Test {
+            #[serde(rename = "NAME")]
+            name: String,
+            #[serde(rename = "DESC")]
+            description: Option<String>,
+            #[serde(rename = "DEPENDS")]
+            depends: Vec<String>,
+        }
+
+        let val = Test {
+            name: "mingw-w64-x86_64-vcdimager".to_owned(),
+            description: None,
+            depends: Vec::new(),
+        };
+        assert_eq!(
+            crate::to_string(&val).unwrap(),
+            "%NAME%\nmingw-w64-x86_64-vcdimager\n\n"
+        );
+    }
 }
@@ -0,0 +1,108 @@
+use crate::{Inner, Package, Repository};
+use rustc_hash::FxHashMap;
+use std::sync::Arc;
+
+/// Prefixes of MSYS2 package names, more specific first
+const MSYS2_PREFIXES: &[&str] = &[
+    "mingw-w64-clang-aarch64-",
+    "mingw-w64-clang-x86_64-",
+    "mingw-w64-clang-i686-",
+    "mingw-w64-ucrt-x86_64-",
+    "mingw-w64-x86_64-",
+    "mingw-w64-i686-",
+    "mingw-w64-",
+];
+
+/// Rules which turn package names into short names users type, see
+/// [`RepositoryBuilder::name_normalization`]
+///
+/// # Example
+/// ```
+/// use archlinux_repo::NameNormalization;
+///
+/// let normalization = NameNormalization::msys2();
+/// assert_eq!("gtk3", normalization.normalize("mingw-w64-x86_64-GTK3"));
+/// ```
+///
+/// [`RepositoryBuilder::name_normalization`]: crate::RepositoryBuilder::name_normalization
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct NameNormalization {
+    /// prefixes removed from names. Only first matching prefix is removed
+    pub strip_prefixes: Vec<String>,
+    /// convert names to lowercase
+    pub lowercase: bool,
+}
+
+impl NameNormalization {
+    /// Strip MSYS2 `mingw-w64-<arch>-` prefixes and lowercase names
+    pub fn msys2() -> Self {
+        NameNormalization {
+            strip_prefixes: MSYS2_PREFIXES.iter().map(|p| p.to_string()).collect(),
+            lowercase: true,
+        }
+    }
+
+    /// Normalize package name
+    pub fn normalize(&self, name: &str) -> String {
+        let name = if self.lowercase {
+            name.to_lowercase()
+        } else {
+            name.to_owned()
+        };
+        let prefix = self.strip_prefixes.iter().find(|prefix| {
+            let prefix = if self.lowercase {
+                prefix.to_lowercase()
+            } else {
+                prefix.to_string()
+            };
+            name.starts_with(&prefix) && name.len() > prefix.len()
+        });
+        match prefix {
+            Some(prefix) => name[prefix.len()..].to_owned(),
+            None => name,
+        }
+    }
+}
+
+impl Inner {
+    /// Index packages by normalized names. If several packages have the same normalized name,
+    /// first one in repository order is used
+    pub(crate) fn index_aliases(&mut self, normalization: &NameNormalization) {
+        let mut aliases = FxHashMap::default();
+        for package in &self.packages {
+            aliases
+                .entry(normalization.normalize(&package.name))
+                .or_insert_with(|| Arc::clone(package));
+        }
+        self.aliases = aliases;
+    }
+}
+
+impl Repository {
+    /// Find package by exact name like [`Repository::get_package_by_name`], then by normalized
+    /// name if [`RepositoryBuilder::name_normalization`] is set. Will return `None` if package
+    /// cannot be found
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::{NameNormalization, RepositoryBuilder};
+    ///
+    /// let repo = RepositoryBuilder::new("mingw64", "http://repo.msys2.org/mingw/x86_64")
+    ///                 .name_normalization(NameNormalization::msys2())
+    ///                 .load()
+    ///                 .await?;
+    /// assert_eq!("mingw-w64-x86_64-gtk3", repo.resolve_alias("GTK3").unwrap().name);
+    /// ```
+    ///
+    /// [`RepositoryBuilder::name_normalization`]: crate::RepositoryBuilder::name_normalization
+    pub fn resolve_alias(&self, name: &str) -> Option<&Package> {
+        if let Some(package) = self.get_package_by_name(name) {
+            return Some(package);
+        }
+        let normalization = self.options.name_normalization.as_ref()?;
+        self.inner
+            .aliases
+            .get(&normalization.normalize(name))
+            .map(|p| p as &Package)
+    }
+}
@@ -0,0 +1,519 @@
+use crate::data::{parse_timestamp, InvalidTimestampError, PackageFiles};
+use crate::limits::LimitedReader;
+use crate::{
+    HttpResponse, Limit, LimitExceededError, LoadOptions, LoadWarning, Package, Progress, Stage,
+};
+use flate2::read::MultiGzDecoder;
+use futures::channel::mpsc;
+use futures::executor::block_on;
+use futures::{FutureExt, SinkExt, StreamExt};
+use serde::de::DeserializeOwned;
+use std::error::Error;
+use std::io::Read;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tar::Archive;
+
+pub(crate) type SendError = Box<dyn Error + Send + Sync>;
+
+/// How many downloaded chunks can wait for decompression
+const CHUNKS_BUFFER: usize = 16;
+
+/// Consumes archive entries in reader thread
+pub(crate) trait EntryConsumer: Send + 'static {
+    type Output: Send + 'static;
+
+    /// Consume entry which path ends with one of required suffixes
+    fn consume(&mut self, path: String, contents: String) -> Result<(), SendError>;
+
+    /// Consume entry directly from archive. By default entry is read into new string and
+    /// passed to [`EntryConsumer::consume`]. Consumers which don't keep entries can read them
+    /// into reusable buffer instead
+    fn consume_reader(&mut self, path: String, reader: &mut dyn Read) -> Result<(), SendError> {
+        let mut contents = String::new();
+        reader.read_to_string(&mut contents).map_err(unwrap_io)?;
+        self.consume(path, contents)
+    }
+
+    /// Called after last entry is consumed
+    fn finish(self) -> Result<Self::Output, SendError>;
+}
+
+/// `desc` fields written by `repo-add`
+const KNOWN_FIELDS: &[&str] = &[
+    "FILENAME",
+    "NAME",
+    "BASE",
+    "VERSION",
+    "DESC",
+    "GROUPS",
+    "CSIZE",
+    "ISIZE",
+    "MD5SUM",
+    "SHA256SUM",
+    "PGPSIG",
+    "URL",
+    "LICENSE",
+    "ARCH",
+    "BUILDDATE",
+    "PACKAGER",
+    "REPLACES",
+    "CONFLICTS",
+    "PROVIDES",
+    "DEPENDS",
+    "OPTDEPENDS",
+    "MAKEDEPENDS",
+    "CHECKDEPENDS",
+    "DELTAS",
+    "XDATA",
+];
+
+/// Warnings about unknown fields of `desc` entry and entry directory which doesn't match
+/// package name and version
+fn check_desc(path: &str, contents: &str, package: &Package) -> Vec<LoadWarning> {
+    let entry = path.trim_end_matches("/desc");
+    let mut warnings: Vec<LoadWarning> = contents
+        .lines()
+        .filter_map(|line| line.strip_prefix('%')?.strip_suffix('%'))
+        .filter(|field| !KNOWN_FIELDS.contains(field))
+        .map(|field| LoadWarning::UnknownField {
+            entry: entry.to_owned(),
+            field: field.to_owned(),
+        })
+        .collect();
+    let name_version = format!("{}-{}", package.name, package.version);
+    if entry.rsplit('/').next() != Some(name_version.as_str()) {
+        warnings.push(LoadWarning::EntryMismatch {
+            entry: entry.to_owned(),
+            package: name_version,
+        });
+    }
+    warnings
+}
+
+/// Collects `desc` entries and parses them at once. Entries are read into single buffer, one
+/// after another. Output contains warnings about entries with invalid build dates, unknown
+/// fields or mismatching paths
+#[derive(Default)]
+pub(crate) struct Descs {
+    paths: Vec<String>,
+    contents: String,
+    /// End of each entry in `contents`
+    ends: Vec<usize>,
+    tolerate_invalid_dates: bool,
+    keep_raw_desc: bool,
+}
+
+impl Descs {
+    pub(crate) fn new(options: &LoadOptions) -> Self {
+        Descs {
+            tolerate_invalid_dates: options.tolerate_invalid_dates,
+            keep_raw_desc: options.keep_raw_desc,
+            ..Descs::default()
+        }
+    }
+}
+
+impl EntryConsumer for Descs {
+    type Output = (Vec<Package>, Vec<LoadWarning>);
+
+    fn consume(&mut self, path: String, contents: String) -> Result<(), SendError> {
+        self.paths.push(path);
+        self.contents.push_str(&contents);
+        self.ends.push(self.contents.len());
+        Ok(())
+    }
+
+    fn consume_reader(&mut self, path: String, reader: &mut dyn Read) -> Result<(), SendError> {
+        reader
+            .read_to_string(&mut self.contents)
+            .map_err(unwrap_io)?;
+        self.paths.push(path);
+        self.ends.push(self.contents.len());
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Self::Output, SendError> {
+        let starts = std::iter::once(0).chain(self.ends.iter().copied());
+        let entries: Vec<&str> = starts
+            .zip(&self.ends)
+            .map(|(start, &end)| &self.contents[start..end])
+            .collect();
+        let mut packages = Vec::with_capacity(entries.len());
+        let mut warnings = Vec::new();
+        let results = parse_all(&entries);
+        for ((path, &contents), result) in self.paths.iter().zip(&entries).zip(results) {
+            let (mut package, warning) = match result {
+                Ok(package) => (package, None),
+                Err(e) => recover_build_date(path, contents, e, self.tolerate_invalid_dates)?,
+            };
+            if self.keep_raw_desc {
+                package.raw_desc = Some(Arc::from(contents));
+            }
+            warnings.extend(warning);
+            warnings.extend(check_desc(path, contents, &package));
+            packages.push(package);
+        }
+        Ok((packages, warnings))
+    }
+}
+
+/// Parse single desc entry, recovering invalid build date, see [`recover_build_date`]
+pub(crate) fn parse_desc(
+    path: &str,
+    contents: &str,
+    tolerate_invalid_dates: bool,
+) -> Result<(Package, Option<LoadWarning>), SendError> {
+    match archlinux_repo_parser::from_str(contents) {
+        Ok(package) => Ok((package, None)),
+        Err(e) => recover_build_date(path, contents, e, tolerate_invalid_dates),
+    }
+}
+
+/// Handle desc entry which failed to parse. If entry has invalid `%BUILDDATE%`, fails with
+/// [`InvalidTimestampError`] or, if invalid dates are tolerated, parses entry with unix epoch
+/// as build date. Other errors are returned as is
+fn recover_build_date(
+    path: &str,
+    contents: &str,
+    error: archlinux_repo_parser::Error,
+    tolerate: bool,
+) -> Result<(Package, Option<LoadWarning>), SendError> {
+    let mut lines = contents.lines();
+    let value = match lines.find(|l| *l == "%BUILDDATE%").and(lines.next()) {
+        Some(value) if parse_timestamp(value).is_none() => value,
+        _ => return Err(Box::new(error)),
+    };
+    if !tolerate {
+        return Err(Box::new(InvalidTimestampError::new(value)));
+    }
+    let fixed = contents.replacen(&format!("%BUILDDATE%\n{}", value), "%BUILDDATE%\n0", 1);
+    let warning = LoadWarning::InvalidBuildDate {
+        entry: path.trim_end_matches("/desc").to_owned(),
+        value: value.to_owned(),
+    };
+    Ok((archlinux_repo_parser::from_str(&fixed)?, Some(warning)))
+}
+
+/// Collects `files` entries and parses them at once. Output contains entry paths
+#[derive(Default)]
+pub(crate) struct Files {
+    paths: Vec<String>,
+    contents: Vec<String>,
+}
+
+impl EntryConsumer for Files {
+    type Output = Vec<(String, PackageFiles)>;
+
+    fn consume(&mut self, path: String, contents: String) -> Result<(), SendError> {
+        self.paths.push(path);
+        self.contents.push(contents);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Self::Output, SendError> {
+        let files = parse_all(&self.contents)
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(self.paths.into_iter().zip(files).collect())
+    }
+}
+
+/// Collects both `desc` and `files` entries of files database
+pub(crate) struct DescsAndFiles {
+    descs: Descs,
+    files: Files,
+}
+
+impl DescsAndFiles {
+    pub(crate) fn new(options: &LoadOptions) -> Self {
+        DescsAndFiles {
+            descs: Descs::new(options),
+            files: Files::default(),
+        }
+    }
+}
+
+impl EntryConsumer for DescsAndFiles {
+    type Output = (
+        (Vec<Package>, Vec<LoadWarning>),
+        Vec<(String, PackageFiles)>,
+    );
+
+    fn consume(&mut self, path: String, contents: String) -> Result<(), SendError> {
+        if path.ends_with("/desc") {
+            self.descs.consume(path, contents)
+        } else {
+            self.files.consume(path, contents)
+        }
+    }
+
+    fn consume_reader(&mut self, path: String, reader: &mut dyn Read) -> Result<(), SendError> {
+        if path.ends_with("/desc") {
+            self.descs.consume_reader(path, reader)
+        } else {
+            self.files.consume_reader(path, reader)
+        }
+    }
+
+    fn finish(self) -> Result<Self::Output, SendError> {
+        Ok((self.descs.finish()?, self.files.finish()?))
+    }
+}
+
+/// Parses `desc` entries one by one and sends packages to channel. Entries are read into
+/// single reused buffer. Warnings about invalid build dates are logged
+pub(crate) struct PackageSender {
+    sender: mpsc::Sender<Package>,
+    tolerate_invalid_dates: bool,
+    keep_raw_desc: bool,
+    buffer: String,
+}
+
+impl PackageSender {
+    pub(crate) fn new(sender: mpsc::Sender<Package>, options: &LoadOptions) -> Self {
+        PackageSender {
+            sender,
+            tolerate_invalid_dates: options.tolerate_invalid_dates,
+            keep_raw_desc: options.keep_raw_desc,
+            buffer: String::new(),
+        }
+    }
+
+    fn send(&mut self, path: &str, contents: &str) -> Result<(), SendError> {
+        let (mut package, warning) = parse_desc(path, contents, self.tolerate_invalid_dates)?;
+        if let Some(warning) = warning {
+            log::warn!("[archlinux-repo-rs] {}", warning);
+        }
+        if self.keep_raw_desc {
+            package.raw_desc = Some(Arc::from(contents));
+        }
+        block_on(self.sender.send(package))?;
+        Ok(())
+    }
+}
+
+impl EntryConsumer for PackageSender {
+    type Output = ();
+
+    fn consume(&mut self, path: String, contents: String) -> Result<(), SendError> {
+        self.send(&path, &contents)
+    }
+
+    fn consume_reader(&mut self, path: String, reader: &mut dyn Read) -> Result<(), SendError> {
+        let mut buffer = std::mem::take(&mut self.buffer);
+        buffer.clear();
+        let result = match reader.read_to_string(&mut buffer) {
+            Ok(_) => self.send(&path, &buffer),
+            Err(e) => Err(unwrap_io(e)),
+        };
+        self.buffer = buffer;
+        result
+    }
+
+    fn finish(self) -> Result<Self::Output, SendError> {
+        Ok(())
+    }
+}
+
+/// Parse desc-formatted entries preserving their order, every entry has its own result. With `parallel` feature entries are
+/// parsed on all CPU cores
+#[cfg(feature = "parallel")]
+fn parse_all<T, E>(entries: &[E]) -> Vec<Result<T, archlinux_repo_parser::Error>>
+where
+    T: DeserializeOwned + Send,
+    E: AsRef<str> + Sync,
+{
+    use rayon::prelude::*;
+    entries
+        .par_iter()
+        .map(|entry| archlinux_repo_parser::from_str(entry.as_ref()))
+        .collect()
+}
+
+#[cfg(not(feature = "parallel"))]
+fn parse_all<T, E>(entries: &[E]) -> Vec<Result<T, archlinux_repo_parser::Error>>
+where
+    T: DeserializeOwned + Send,
+    E: AsRef<str> + Sync,
+{
+    entries
+        .iter()
+        .map(|entry| archlinux_repo_parser::from_str(entry.as_ref()))
+        .collect()
+}
+
+enum Event<T> {
+    Progress(Progress),
+    Done(Result<T, SendError>),
+}
+
+/// Blocking reader over chunks sent by async code. Counts read bytes
+struct ChunkReader {
+    chunks: mpsc::Receiver<Vec<u8>>,
+    chunk: Vec<u8>,
+    position: usize,
+    bytes_read: Arc<AtomicU64>,
+}
+
+impl Read for ChunkReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.position == self.chunk.len() {
+            match block_on(self.chunks.next()) {
+                Some(chunk) => {
+                    self.chunk = chunk;
+                    self.position = 0;
+                }
+                None => return Ok(0),
+            }
+        }
+        let read = buf.len().min(self.chunk.len() - self.position);
+        buf[..read].copy_from_slice(&self.chunk[self.position..self.position + read]);
+        self.position += read;
+        self.bytes_read.fetch_add(read as u64, Ordering::Relaxed);
+        Ok(read)
+    }
+}
+
+/// Read gzipped tar archive from response. Decompression, tar iteration and `consumer` run
+/// in separate thread while response body is downloaded, so the caller's executor is never
+/// blocked. `progress` is called from the calling task only
+pub(crate) async fn read_archive<C, P>(
+    mut response: HttpResponse,
+    stages: (Stage, Stage),
+    suffixes: &'static [&'static str],
+    options: &LoadOptions,
+    consumer: C,
+    progress: P,
+) -> Result<C::Output, Box<dyn Error>>
+where
+    C: EntryConsumer,
+    P: Fn(Progress),
+{
+    let (loading, reading) = stages;
+    let limits = options.limits;
+    let options = options.clone();
+    let length = response.content_length();
+    let compressed_limit = |size: u64| match limits.compressed_size {
+        Some(max) if size > max => Err(LimitExceededError::new(Limit::CompressedSize, max)),
+        _ => Ok(()),
+    };
+    compressed_limit(length.unwrap_or(0))?;
+    let (mut chunk_sender, chunks) = mpsc::channel(CHUNKS_BUFFER);
+    let (event_sender, mut events) = mpsc::unbounded();
+    let bytes_read = Arc::new(AtomicU64::new(0));
+    let reader = ChunkReader {
+        chunks,
+        chunk: Vec::new(),
+        position: 0,
+        bytes_read: bytes_read.clone(),
+    };
+    std::thread::spawn(move || {
+        let result = read_entries(
+            reader,
+            reading,
+            suffixes,
+            &options,
+            consumer,
+            |items_done, path| {
+                let _ = event_sender.unbounded_send(Event::Progress(Progress {
+                    items_done,
+                    bytes_done: bytes_read.load(Ordering::Relaxed),
+                    bytes_total: length,
+                    current: Some(path),
+                    ..Progress::new(reading)
+                }));
+            },
+        );
+        let _ = event_sender.unbounded_send(Event::Done(result));
+    });
+
+    let mut bytes_downloaded: u64 = 0;
+    while let Some(chunk) = response.chunk().await? {
+        while let Some(Some(event)) = events.next().now_or_never() {
+            match event {
+                Event::Progress(p) => progress(p),
+                Event::Done(result) => return result.map_err(|e| e as Box<dyn Error>),
+            }
+        }
+        bytes_downloaded += chunk.len() as u64;
+        compressed_limit(bytes_downloaded)?;
+        if chunk_sender.send(chunk).await.is_err() {
+            // reader thread stopped, its result is in events
+            break;
+        }
+        progress(Progress::bytes(loading, bytes_downloaded, length));
+    }
+    drop(chunk_sender);
+    while let Some(event) = events.next().await {
+        match event {
+            Event::Progress(p) => progress(p),
+            Event::Done(result) => return result.map_err(|e| e as Box<dyn Error>),
+        }
+    }
+    Err("archive reader thread panicked".into())
+}
+
+/// Read gzipped tar archive from blocking reader in the calling thread
+pub(crate) fn read_entries<R, C, P>(
+    reader: R,
+    stage: Stage,
+    suffixes: &[&str],
+    options: &LoadOptions,
+    mut consumer: C,
+    progress: P,
+) -> Result<C::Output, SendError>
+where
+    R: Read,
+    C: EntryConsumer,
+    P: Fn(u64, String),
+{
+    let started = Instant::now();
+    let mut parsing = Duration::ZERO;
+    let limits = options.limits;
+    let decoder = LimitedReader::new(
+        MultiGzDecoder::new(reader),
+        Limit::DecompressedSize,
+        limits.decompressed_size,
+    );
+    let mut archive = Archive::new(decoder);
+    let mut items_done = 0;
+    for entry_result in archive.entries().map_err(unwrap_io)? {
+        let mut entry = entry_result.map_err(unwrap_io)?;
+        let path = entry.path()?.to_str().unwrap().to_owned();
+        if suffixes.iter().any(|suffix| path.ends_with(suffix)) {
+            if let Some(max) = limits.entry_size {
+                if entry.size() > max {
+                    return Err(Box::new(LimitExceededError::new(Limit::EntrySize, max)));
+                }
+            }
+            items_done += 1;
+            progress(items_done, path.clone());
+            let consume_started = Instant::now();
+            consumer.consume_reader(path.clone(), &mut entry)?;
+            parsing += consume_started.elapsed();
+            options.event(|e| e.on_entry_parsed(&path));
+        }
+    }
+    let finish_started = Instant::now();
+    let output = consumer.finish()?;
+    parsing += finish_started.elapsed();
+    if let Some(metrics) = &options.metrics {
+        metrics.archive_decompressed(stage, started.elapsed().saturating_sub(parsing));
+        metrics.entries_parsed(stage, items_done, parsing);
+    }
+    Ok(output)
+}
+
+/// Extract [`LimitExceededError`] from IO error, so it can be downcasted by the caller
+fn unwrap_io(error: std::io::Error) -> SendError {
+    if error
+        .get_ref()
+        .is_some_and(|e| e.is::<LimitExceededError>())
+    {
+        error.into_inner().unwrap()
+    } else {
+        Box::new(error)
+    }
+}
@@ -0,0 +1,244 @@
+//! Command line client for Arch Linux repositories
+//!
+//! ```text
+//! arch-repo --url https://mirror.msys2.org/mingw/mingw64 search gtk3
+//! arch-repo --url https://mirror.msys2.org/mingw/mingw64 download mingw-w64-x86_64-gtk3
+//! ```
+use archlinux_repo::{Package, PackageArchive, Repository, RepositoryBuilder, VerifyOptions};
+use clap::{Parser, Subcommand};
+use std::error::Error;
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(name = "arch-repo", version, about = "Inspect Arch Linux repositories")]
+struct Args {
+    /// Repository url
+    #[arg(short, long)]
+    url: String,
+    /// Repository name. Discovered from directory listing if not set
+    #[arg(short, long)]
+    name: Option<String>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Find packages which name or description contains pattern
+    Search { pattern: String },
+    /// Show package metadata
+    Info { package: String },
+    /// List package files
+    Files { package: String },
+    /// List package dependencies
+    Depends {
+        package: String,
+        /// Also list optional, build and check dependencies
+        #[arg(short, long)]
+        all: bool,
+    },
+    /// Download package file
+    Download {
+        package: String,
+        /// Directory to save package into
+        #[arg(short, long, default_value = ".")]
+        output: PathBuf,
+        /// Download detached signature too
+        #[arg(short, long)]
+        signature: bool,
+    },
+    /// Compare package contents with files database
+    Verify {
+        package: String,
+        /// Verify local package file instead of downloading it
+        #[arg(short, long)]
+        file: Option<PathBuf>,
+    },
+    /// Check that every package file exists on server and has expected size
+    VerifyRepo {
+        /// How many requests can be sent at once
+        #[arg(short, long, default_value_t = 8)]
+        concurrency: usize,
+        /// How many packages to download to check their checksums
+        #[arg(short = 's', long, default_value_t = 0)]
+        checksums: usize,
+        /// Print report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = Args::parse();
+    match run(args).await {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(args: Args) -> Result<ExitCode, Box<dyn Error>> {
+    let builder = match args.name {
+        Some(name) => RepositoryBuilder::new(&name, &args.url),
+        None => RepositoryBuilder::discover(&args.url).await?,
+    };
+    let repo = builder.load().await?;
+    match args.command {
+        Command::Search { pattern } => {
+            let pattern = pattern.to_lowercase();
+            for package in &repo {
+                let description = package.description.as_deref().unwrap_or("");
+                if package.name.to_lowercase().contains(&pattern)
+                    || description.to_lowercase().contains(&pattern)
+                {
+                    println!("{} {}\n    {}", package.name, package.version, description);
+                }
+            }
+        }
+        Command::Info { package } => print_info(find(&repo, &package)?),
+        Command::Files { package } => {
+            let name = find(&repo, &package)?.name.clone();
+            repo.load_files_metadata().await?;
+            let files = repo
+                .get_package_files(&name)
+                .ok_or("files metadata not found")?;
+            for file in files {
+                println!("{}", file);
+            }
+        }
+        Command::Depends { package, all } => {
+            let package = find(&repo, &package)?;
+            let mut kinds = vec![("depends", &package.depends)];
+            if all {
+                kinds.push(("optdepends", &package.optdepends));
+                kinds.push(("makedepends", &package.makedepends));
+                kinds.push(("checkdepends", &package.checkdepends));
+            }
+            for (kind, dependencies) in kinds {
+                for dependency in dependencies.iter().flatten() {
+                    if all {
+                        println!("{} {}", kind, dependency);
+                    } else {
+                        println!("{}", dependency);
+                    }
+                }
+            }
+        }
+        Command::Download {
+            package,
+            output,
+            signature,
+        } => {
+            let package = find(&repo, &package)?;
+            let response = repo.request_package(&package.name).await?;
+            if !response.is_success() {
+                return Err(format!("server returned {} status", response.status()).into());
+            }
+            let file_name = package.safe_file_name()?;
+            let path = output.join(file_name);
+            std::fs::write(&path, response.bytes().await?)?;
+            println!("{}", path.display());
+            if signature {
+                let response = repo.request_package_signature(&package.name).await?;
+                let path = output.join(format!("{}.sig", file_name));
+                std::fs::write(&path, response.bytes().await?)?;
+                println!("{}", path.display());
+            }
+        }
+        Command::Verify { package, file } => {
+            let name = find(&repo, &package)?.name.clone();
+            let mismatch = match file {
+                Some(file) => {
+                    repo.load_files_metadata().await?;
+                    repo.verify_package_files(&name, &PackageArchive::open(file)?)?
+                }
+                None => repo.verify_files(&name).await?,
+            };
+            for file in &mismatch.missing_in_package {
+                println!("missing in package: {}", file);
+            }
+            for file in &mismatch.missing_in_database {
+                println!("missing in database: {}", file);
+            }
+            if !mismatch.is_empty() {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Command::VerifyRepo {
+            concurrency,
+            checksums,
+            json,
+        } => {
+            let options = VerifyOptions::new()
+                .concurrency(concurrency)
+                .checksum_sample(checksums);
+            let report = repo.verify_repo(options).await;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                for problem in &report.problems {
+                    println!("{:?}", problem);
+                }
+                println!(
+                    "checked {} packages, {} checksums, found {} problems",
+                    report.checked,
+                    report.checksums_checked,
+                    report.problems.len()
+                );
+            }
+            if !report.is_ok() {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Find package by name, name with version or base name
+fn find<'a>(repo: &'a Repository, name: &str) -> Result<&'a Package, Box<dyn Error>> {
+    repo.get_package_by_name(name)
+        .or_else(|| repo.get_package_by_name_and_version(name))
+        .or_else(|| repo.get_packages_by_base(name).into_iter().next())
+        .ok_or_else(|| format!("package {} not found", name).into())
+}
+
+fn print_info(package: &Package) {
+    println!("Name           : {}", package.name);
+    println!("Base           : {}", package.base.as_deref().unwrap_or(""));
+    println!("Version        : {}", package.version);
+    println!(
+        "Description    : {}",
+        package.description.as_deref().unwrap_or("")
+    );
+    println!("Architecture   : {}", package.architecture);
+    println!(
+        "URL            : {}",
+        package.home_url.as_deref().unwrap_or("")
+    );
+    println!("Licenses       : {}", join(&package.license));
+    println!("Groups         : {}", join(&package.groups));
+    println!("Provides       : {}", join(&package.provides));
+    println!("Depends On     : {}", join(&package.depends));
+    println!("Conflicts With : {}", join(&package.conflicts));
+    println!("Replaces       : {}", join(&package.replaces));
+    println!("Download Size  : {}", package.compressed_size());
+    println!("Installed Size : {}", package.installed_size());
+    println!("Packager       : {}", package.packager);
+    println!("Build Date     : {}", package.build_date);
+    println!("File Name      : {}", package.file_name);
+    println!("SHA-256 Sum    : {}", package.sha256_sum);
+}
+
+fn join<T: Display>(values: &Option<Vec<T>>) -> String {
+    values
+        .iter()
+        .flatten()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,140 @@
+use crate::package::{invalid, key_values, timestamp};
+use crate::PackageInfoError;
+use chrono::{DateTime, Utc};
+use std::str::FromStr;
+
+/// Package which was installed in build environment, like `glibc-2.35-1-x86_64`
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct InstalledDependency {
+    pub name: String,
+    /// version with release, like `2.35-1`
+    pub version: String,
+    pub architecture: String,
+}
+
+impl FromStr for InstalledDependency {
+    type Err = PackageInfoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.rsplitn(4, '-');
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(architecture), Some(release), Some(version), Some(name)) => {
+                Ok(InstalledDependency {
+                    name: name.to_owned(),
+                    version: format!("{}-{}", version, release),
+                    architecture: architecture.to_owned(),
+                })
+            }
+            _ => Err(invalid("installed", s)),
+        }
+    }
+}
+
+/// Build environment description from `.BUILDINFO` member of package archive
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct BuildInfo {
+    /// `.BUILDINFO` format version
+    pub format: Option<u32>,
+    pub name: String,
+    pub base: Option<String>,
+    pub version: String,
+    pub architecture: Option<String>,
+    /// checksum of PKGBUILD used to build package
+    pub pkgbuild_sha256_sum: Option<String>,
+    /// who created this package
+    pub packager: Option<String>,
+    pub build_date: Option<DateTime<Utc>>,
+    /// directory where package was built
+    pub build_dir: Option<String>,
+    /// directory with PKGBUILD
+    pub start_dir: Option<String>,
+    /// tool used to build package, like `devtools`
+    pub build_tool: Option<String>,
+    pub build_tool_version: Option<String>,
+    /// makepkg `BUILDENV` flags
+    pub build_env: Vec<String>,
+    /// makepkg `OPTIONS` flags, like `strip` or `!debug`
+    pub options: Vec<String>,
+    /// packages installed in build environment
+    pub installed: Vec<InstalledDependency>,
+    /// fields which are not listed above, in file order
+    pub extra: Vec<(String, String)>,
+}
+
+impl FromStr for BuildInfo {
+    type Err = PackageInfoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut info = BuildInfo::default();
+        for line in key_values(s) {
+            let (key, value) = line?;
+            match key {
+                "format" => info.format = Some(value.parse().map_err(|_| invalid(key, value))?),
+                "pkgname" => info.name = value.to_owned(),
+                "pkgbase" => info.base = Some(value.to_owned()),
+                "pkgver" => info.version = value.to_owned(),
+                "pkgarch" => info.architecture = Some(value.to_owned()),
+                "pkgbuild_sha256sum" => info.pkgbuild_sha256_sum = Some(value.to_owned()),
+                "packager" => info.packager = Some(value.to_owned()),
+                "builddate" => info.build_date = Some(timestamp(key, value)?),
+                "builddir" => info.build_dir = Some(value.to_owned()),
+                "startdir" => info.start_dir = Some(value.to_owned()),
+                "buildtool" => info.build_tool = Some(value.to_owned()),
+                "buildtoolver" => info.build_tool_version = Some(value.to_owned()),
+                "buildenv" => info.build_env.push(value.to_owned()),
+                "options" => info.options.push(value.to_owned()),
+                "installed" => info.installed.push(InstalledDependency::from_str(value)?),
+                _ => info.extra.push((key.to_owned(), value.to_owned())),
+            }
+        }
+        if info.name.is_empty() {
+            return Err(PackageInfoError::MissingField("pkgname"));
+        }
+        if info.version.is_empty() {
+            return Err(PackageInfoError::MissingField("pkgver"));
+        }
+        Ok(info)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{BuildInfo, PackageInfoError};
+    use std::str::FromStr;
+
+    #[test]
+    fn parse_buildinfo() {
+        let info = BuildInfo::from_str(
+            "format = 2\n\
+            pkgname = foo\n\
+            pkgbase = foo\n\
+            pkgver = 1.0-1\n\
+            pkgarch = x86_64\n\
+            builddate = 1560520506\n\
+            builddir = /build\n\
+            buildenv = check\n\
+            options = strip\n\
+            options = !debug\n\
+            installed = gcc-libs-12.1.0-2-x86_64\n\
+            installed = python-3.10.5-1-x86_64\n",
+        )
+        .unwrap();
+        assert_eq!(Some(2), info.format);
+        assert_eq!(Some("/build".to_owned()), info.build_dir);
+        assert_eq!(vec!["strip", "!debug"], info.options);
+        assert_eq!("gcc-libs", info.installed[0].name);
+        assert_eq!("12.1.0-2", info.installed[0].version);
+        assert_eq!("x86_64", info.installed[1].architecture);
+    }
+
+    #[test]
+    fn invalid_installed_package() {
+        assert_eq!(
+            Err(PackageInfoError::InvalidValue {
+                field: "installed".to_owned(),
+                value: "gcc".to_owned()
+            }),
+            BuildInfo::from_str("pkgname = foo\npkgver = 1.0-1\ninstalled = gcc\n")
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use crate::sanitize::check_file_name;
+use crate::HttpResponse;
+use futures::channel::oneshot;
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+
+/// Default pacman package cache directory
+pub(crate) const PACMAN_CACHE_DIR: &str = "/var/cache/pacman/pkg";
+
+/// Size of chunks in which cached package is returned
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Find package file with matching SHA256 checksum in cache directories. Files are read and
+/// hashed in separate thread
+pub(crate) async fn cached_package(
+    dirs: &[PathBuf],
+    file_name: &str,
+    sha256: &str,
+) -> Option<HttpResponse> {
+    if dirs.is_empty() || check_file_name(file_name).is_err() {
+        return None;
+    }
+    let paths: Vec<PathBuf> = dirs.iter().map(|dir| dir.join(file_name)).collect();
+    let sha256 = sha256.to_lowercase();
+    let (sender, receiver) = oneshot::channel();
+    std::thread::spawn(move || {
+        let file = paths.iter().find_map(|path| {
+            let contents = std::fs::read(path).ok()?;
+            if format!("{:x}", Sha256::digest(&contents)) == sha256 {
+                Some(contents)
+            } else {
+                log::debug!(
+                    "[archlinux-repo-rs] Ignoring cached {} with wrong checksum",
+                    path.display()
+                );
+                None
+            }
+        });
+        let _ = sender.send(file);
+    });
+    let contents = receiver.await.ok()??;
+    let length = contents.len() as u64;
+    let chunks: Vec<_> = contents
+        .chunks(CHUNK_SIZE)
+        .map(|chunk| Ok(chunk.to_vec()))
+        .collect();
+    Some(HttpResponse::new(
+        200,
+        Some(length),
+        Box::pin(futures::stream::iter(chunks)),
+    ))
+}
@@ -0,0 +1,133 @@
+use crate::{Package, PackageBuilder};
+use std::fmt::{Display, Formatter};
+
+/// Compression of package file, like makepkg's `PKGEXT`
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug, Default)]
+pub enum PackageCompression {
+    /// `.pkg.tar.zst`, default since pacman 5.2
+    #[default]
+    Zstd,
+    /// `.pkg.tar.xz`
+    Xz,
+    /// `.pkg.tar.gz`
+    Gzip,
+    /// `.pkg.tar.bz2`
+    Bzip2,
+    /// `.pkg.tar.lz4`
+    Lz4,
+    /// `.pkg.tar.lz`
+    Lzip,
+    /// `.pkg.tar.lrz`
+    Lrzip,
+    /// `.pkg.tar.lzo`
+    Lzop,
+    /// `.pkg.tar.Z`
+    Compress,
+    /// `.pkg.tar`, uncompressed
+    None,
+}
+
+impl PackageCompression {
+    const ALL: [PackageCompression; 10] = [
+        PackageCompression::Zstd,
+        PackageCompression::Xz,
+        PackageCompression::Gzip,
+        PackageCompression::Bzip2,
+        PackageCompression::Lz4,
+        PackageCompression::Lzip,
+        PackageCompression::Lrzip,
+        PackageCompression::Lzop,
+        PackageCompression::Compress,
+        PackageCompression::None,
+    ];
+
+    /// Package file extension, like `.pkg.tar.zst`
+    pub fn extension(&self) -> &'static str {
+        match self {
+            PackageCompression::Zstd => ".pkg.tar.zst",
+            PackageCompression::Xz => ".pkg.tar.xz",
+            PackageCompression::Gzip => ".pkg.tar.gz",
+            PackageCompression::Bzip2 => ".pkg.tar.bz2",
+            PackageCompression::Lz4 => ".pkg.tar.lz4",
+            PackageCompression::Lzip => ".pkg.tar.lz",
+            PackageCompression::Lrzip => ".pkg.tar.lrz",
+            PackageCompression::Lzop => ".pkg.tar.lzo",
+            PackageCompression::Compress => ".pkg.tar.Z",
+            PackageCompression::None => ".pkg.tar",
+        }
+    }
+
+    /// Detect compression by package file name. Returns `None` if file name doesn't end with
+    /// known package extension
+    pub fn from_file_name(file_name: &str) -> Option<Self> {
+        PackageCompression::ALL
+            .iter()
+            .copied()
+            .find(|c| file_name.ends_with(c.extension()))
+    }
+}
+
+impl Display for PackageCompression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+impl Package {
+    /// Extension of package file, like `.pkg.tar.zst`. Returns `None` if file name doesn't end
+    /// with known package extension
+    pub fn file_extension(&self) -> Option<&'static str> {
+        self.compression().map(|c| c.extension())
+    }
+
+    /// Compression of package file detected by file name extension
+    pub fn compression(&self) -> Option<PackageCompression> {
+        PackageCompression::from_file_name(&self.file_name)
+    }
+}
+
+impl PackageBuilder {
+    /// Set compression used in default file name `name-version-arch.pkg.tar.*`. Defaults to
+    /// [`PackageCompression::Zstd`]. Ignored if file name is set with
+    /// [`PackageBuilder::file_name`]
+    ///
+    /// # Example
+    /// ```
+    /// use archlinux_repo::{PackageBuilder, PackageCompression};
+    ///
+    /// let package = PackageBuilder::new("foo", "1.0-1", "x86_64")
+    ///     .compression(PackageCompression::Xz)
+    ///     .compressed_size(1024)
+    ///     .sha256_sum("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!("foo-1.0-1-x86_64.pkg.tar.xz", package.file_name);
+    /// assert_eq!(Some(PackageCompression::Xz), package.compression());
+    /// ```
+    pub fn compression(mut self, compression: PackageCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::PackageCompression;
+
+    #[test]
+    fn compression_is_detected_by_file_name() {
+        assert_eq!(
+            Some(PackageCompression::Zstd),
+            PackageCompression::from_file_name("foo-1.0-1-any.pkg.tar.zst")
+        );
+        assert_eq!(
+            Some(PackageCompression::Lzip),
+            PackageCompression::from_file_name("foo-1.0-1-any.pkg.tar.lz")
+        );
+        assert_eq!(
+            Some(PackageCompression::None),
+            PackageCompression::from_file_name("foo-1.0-1-any.pkg.tar")
+        );
+        assert_eq!(None, PackageCompression::from_file_name("foo-1.0-1.tar.gz"));
+    }
+}
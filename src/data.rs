@@ -1,7 +1,11 @@
-use chrono::{DateTime, Utc};
+use crate::version::{hash_version, total_cmp};
+use crate::{vercmp, Delta};
+use chrono::{DateTime, TimeZone, Utc};
 use serde::de::Visitor;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
 use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -30,7 +34,7 @@ impl Display for DependencyConstraintsParseError {
 
 impl std::error::Error for DependencyConstraintsParseError {}
 
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd, Debug)]
 pub enum DependencyConstraints {
     /// <
     LessThan,
@@ -95,7 +99,11 @@ impl Display for DependencyVersionParseError {
 
 impl std::error::Error for DependencyVersionParseError {}
 
-#[derive(Clone, Eq, PartialEq, Debug)]
+/// Version constraint of dependency. Constraints are ordered by [`Version`], then by version
+/// string and constraint
+///
+/// [`Version`]: crate::Version
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
 pub struct DependencyVersion {
     pub constraint: DependencyConstraints,
     pub version: String,
@@ -151,6 +159,48 @@ impl FromStr for DependencyVersion {
     }
 }
 
+impl Ord for DependencyVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // versions like `1.01` and `1.1` are equal for `Version`, but not for `Eq`
+        total_cmp(&self.version, &other.version)
+            .then_with(|| self.version.cmp(&other.version))
+            .then_with(|| self.constraint.cmp(&other.constraint))
+    }
+}
+
+impl PartialOrd for DependencyVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl DependencyVersion {
+    /// Check whether `version` satisfies constraint. Versions are compared with [`vercmp`], so
+    /// epochs are respected and release is compared only if constraint has it
+    ///
+    /// # Example
+    /// ```
+    /// use archlinux_repo::DependencyVersion;
+    /// use std::str::FromStr;
+    ///
+    /// let constraint = DependencyVersion::from_str(">=1:9.9-1").unwrap();
+    /// assert!(constraint.is_satisfied_by("2:1.0-1"));
+    /// assert!(!constraint.is_satisfied_by("10.0-1"));
+    /// ```
+    ///
+    /// [`vercmp`]: crate::vercmp
+    pub fn is_satisfied_by(&self, version: &str) -> bool {
+        let ordering = vercmp(version, &self.version);
+        match self.constraint {
+            DependencyConstraints::LessThan => ordering == Ordering::Less,
+            DependencyConstraints::MoreThan => ordering == Ordering::Greater,
+            DependencyConstraints::Equals => ordering == Ordering::Equal,
+            DependencyConstraints::MoreOrEqualsThan => ordering != Ordering::Less,
+            DependencyConstraints::LessOrEqualsThan => ordering != Ordering::Greater,
+        }
+    }
+}
+
 impl Display for DependencyVersion {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let dep = self.constraint.to_string() + &self.version;
@@ -158,18 +208,26 @@ impl Display for DependencyVersion {
     }
 }
 
-#[derive(Clone, Eq, PartialEq, Debug)]
+/// Package dependency. Dependencies are ordered by name, then by version constraint
+#[derive(Clone, Eq, PartialEq, Hash, Ord, PartialOrd, Debug)]
 pub struct Dependency {
     /// dependency name
-    pub name: String,
+    pub name: Arc<str>,
     /// dependency version constraint. If None - match all dependencies with given name
     pub version: Option<DependencyVersion>,
+    /// reason after `: `, mostly used by optional dependencies, like `for foo support`
+    pub description: Option<String>,
 }
 
 impl FromStr for Dependency {
     type Err = DependencyVersionParseError;
 
     fn from_str(value: &str) -> Result<Self, Self::Err> {
+        // `:` without space belongs to version epoch
+        let (value, description) = match value.split_once(": ") {
+            Some((value, description)) => (value.trim_end(), Some(description.trim().to_owned())),
+            None => (value, None),
+        };
         if let Some(pos) = value
             .find('<')
             .or_else(|| value.find('>'))
@@ -177,18 +235,52 @@ impl FromStr for Dependency {
         {
             let version = DependencyVersion::from_str(&value[pos..])?;
             Ok(Dependency {
-                name: value[..pos].to_owned(),
+                name: value[..pos].into(),
                 version: Some(version),
+                description,
             })
         } else {
             Ok(Dependency {
-                name: value.to_owned(),
+                name: value.into(),
                 version: None,
+                description,
             })
         }
     }
 }
 
+impl Dependency {
+    /// Check whether package satisfies dependency by its name and version or by one of its
+    /// provisions. Provision without version satisfies only dependency without version
+    /// constraint, like in pacman
+    pub fn is_satisfied_by(&self, package: &Package) -> bool {
+        self.is_satisfied_by_parts(&package.name, &package.version, package.provides.as_deref())
+    }
+
+    /// Same as [`Dependency::is_satisfied_by`] for package given by name, version and
+    /// provisions, like installed package
+    pub(crate) fn is_satisfied_by_parts(
+        &self,
+        name: &str,
+        version: &str,
+        provides: Option<&[String]>,
+    ) -> bool {
+        let satisfies = |version: Option<&str>| match (&self.version, version) {
+            (None, _) => true,
+            (Some(constraint), Some(version)) => constraint.is_satisfied_by(version),
+            (Some(_), None) => false,
+        };
+        (name == &*self.name && satisfies(Some(version)))
+            || provides.unwrap_or_default().iter().any(|provision| {
+                let (name, version) = match provision.split_once('=') {
+                    Some((name, version)) => (name, Some(version)),
+                    None => (provision.as_str(), None),
+                };
+                name == &*self.name && satisfies(version)
+            })
+    }
+}
+
 impl Display for Dependency {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         if let Some(version) = self.version.as_ref() {
@@ -197,6 +289,9 @@ impl Display for Dependency {
         } else {
             f.write_str(&self.name)?;
         }
+        if let Some(description) = self.description.as_ref() {
+            write!(f, ": {}", description)?;
+        }
         Ok(())
     }
 }
@@ -240,8 +335,10 @@ impl Serialize for Dependency {
     }
 }
 
-/// Repository package
-#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug)]
+/// Repository package. Packages are hashed by name and [`Version`]
+///
+/// [`Version`]: crate::Version
+#[derive(Serialize, Deserialize, Clone, Eq, Debug)]
 pub struct Package {
     /// file name
     #[serde(rename = "FILENAME")]
@@ -260,7 +357,7 @@ pub struct Package {
     pub description: Option<String>,
     /// package groups
     #[serde(rename = "GROUPS")]
-    pub groups: Option<Vec<String>>,
+    pub groups: Option<Vec<Arc<str>>>,
     /// tar.xz archive size
     #[serde(rename = "CSIZE")]
     pub compressed_size: u64,
@@ -273,24 +370,24 @@ pub struct Package {
     /// SHA256 checksum
     #[serde(rename = "SHA256SUM")]
     pub sha256_sum: String,
-    /// PGP signature
-    #[serde(rename = "PGPSIG")]
+    /// Base64-encoded PGP signature, empty if database doesn't have it
+    #[serde(rename = "PGPSIG", default, skip_serializing_if = "String::is_empty")]
     pub pgp_signature: String,
     /// package home url
     #[serde(rename = "URL")]
     pub home_url: Option<String>,
     /// license name
     #[serde(rename = "LICENSE")]
-    pub license: Option<Vec<String>>,
+    pub license: Option<Vec<Arc<str>>>,
     /// processor architecture
     #[serde(rename = "ARCH")]
-    pub architecture: String,
+    pub architecture: Arc<str>,
     /// build date
     #[serde(rename = "BUILDDATE", with = "date_serde")]
     pub build_date: DateTime<Utc>,
     /// who created this package
     #[serde(rename = "PACKAGER")]
-    pub packager: String,
+    pub packager: Arc<str>,
     /// packages which this package replaces
     #[serde(rename = "REPLACES")]
     pub replaces: Option<Vec<String>>,
@@ -310,8 +407,11 @@ pub struct Package {
     pub makedepends: Option<Vec<Dependency>>,
     #[serde(rename = "CHECKDEPENDS")]
     pub checkdepends: Option<Vec<Dependency>>,
+    /// binary deltas, written only by legacy repo-add versions
+    #[serde(rename = "DELTAS", default, skip_serializing_if = "Option::is_none")]
+    pub deltas: Option<Vec<Delta>>,
     /// VCS packages with same name. For example `test-git-1.0` is a VCS package for `test-1.0`.
-    /// Supported prefixes are:
+    /// Default suffixes, which can be changed with `RepositoryBuilder::vcs_suffixes`, are:
     /// - cvs
     /// - svn
     /// - hg
@@ -320,9 +420,147 @@ pub struct Package {
     /// - git
     #[serde(skip)]
     pub linked_sources: Vec<Arc<Package>>,
+    /// Original desc entry, kept only if enabled with `RepositoryBuilder::keep_raw_desc`
+    #[serde(skip)]
+    pub(crate) raw_desc: Option<Arc<str>>,
+}
+
+impl PartialEq for Package {
+    /// Raw desc entry is not compared: it depends only on loading options
+    fn eq(&self, other: &Self) -> bool {
+        let Package {
+            file_name,
+            name,
+            base,
+            version,
+            description,
+            groups,
+            compressed_size,
+            installed_size,
+            md5_sum,
+            sha256_sum,
+            pgp_signature,
+            home_url,
+            license,
+            architecture,
+            build_date,
+            packager,
+            replaces,
+            conflicts,
+            provides,
+            depends,
+            optdepends,
+            makedepends,
+            checkdepends,
+            deltas,
+            linked_sources,
+            raw_desc: _,
+        } = self;
+        file_name == &other.file_name
+            && name == &other.name
+            && base == &other.base
+            && version == &other.version
+            && description == &other.description
+            && groups == &other.groups
+            && compressed_size == &other.compressed_size
+            && installed_size == &other.installed_size
+            && md5_sum == &other.md5_sum
+            && sha256_sum == &other.sha256_sum
+            && pgp_signature == &other.pgp_signature
+            && home_url == &other.home_url
+            && license == &other.license
+            && architecture == &other.architecture
+            && build_date == &other.build_date
+            && packager == &other.packager
+            && replaces == &other.replaces
+            && conflicts == &other.conflicts
+            && provides == &other.provides
+            && depends == &other.depends
+            && optdepends == &other.optdepends
+            && makedepends == &other.makedepends
+            && checkdepends == &other.checkdepends
+            && deltas == &other.deltas
+            && linked_sources == &other.linked_sources
+    }
+}
+
+impl Hash for Package {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        hash_version(&self.version, state);
+    }
 }
 
 impl Package {
+    /// Desc entry exactly as it was served by repository. Returns `None` if keeping it is not
+    /// enabled with `RepositoryBuilder::keep_raw_desc` or package was not parsed from
+    /// repository database, like base packages created for VCS packages or packages restored
+    /// from snapshot
+    pub fn raw_desc(&self) -> Option<&str> {
+        self.raw_desc.as_deref()
+    }
+
+    /// Package groups, empty if package has none
+    pub fn groups(&self) -> &[Arc<str>] {
+        slice(&self.groups)
+    }
+
+    /// License names, empty if package has none
+    pub fn licenses(&self) -> &[Arc<str>] {
+        slice(&self.license)
+    }
+
+    /// Packages which this package replaces, empty if package has none
+    pub fn replaces(&self) -> &[String] {
+        slice(&self.replaces)
+    }
+
+    /// Packages which cannot be used with this package, empty if package has none
+    pub fn conflicts(&self) -> &[String] {
+        slice(&self.conflicts)
+    }
+
+    /// Packages provided by this package, empty if package has none
+    pub fn provides(&self) -> &[String] {
+        slice(&self.provides)
+    }
+
+    /// Run-time dependencies, empty if package has none
+    pub fn depends(&self) -> &[Dependency] {
+        slice(&self.depends)
+    }
+
+    /// Optional dependencies, empty if package has none
+    pub fn optdepends(&self) -> &[Dependency] {
+        slice(&self.optdepends)
+    }
+
+    /// Build-time dependencies, empty if package has none
+    pub fn makedepends(&self) -> &[Dependency] {
+        slice(&self.makedepends)
+    }
+
+    /// Test dependencies, empty if package has none
+    pub fn checkdepends(&self) -> &[Dependency] {
+        slice(&self.checkdepends)
+    }
+
+    /// Serialize package into desc format used by repository databases. Missing fields are
+    /// omitted
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::Repository;
+    ///
+    /// let repo = Repository::load("mingw64", "http://repo.msys2.org/mingw/x86_64").await?;
+    /// let mut gtk = repo["mingw-w64-x86_64-gtk3"].clone();
+    /// gtk.description = Some("Patched GTK".to_owned());
+    /// std::fs::write("desc", gtk.to_desc_string()?)?;
+    /// ```
+    pub fn to_desc_string(&self) -> Result<String, archlinux_repo_parser::Error> {
+        archlinux_repo_parser::to_string(self)
+    }
+
     pub fn base_package_for_csv(csv: &Package, suffix: &str) -> Self {
         Package {
             file_name: csv.file_name.clone(),
@@ -333,6 +571,7 @@ impl Package {
             depends: csv.depends.clone(),
             build_date: csv.build_date,
             checkdepends: csv.checkdepends.clone(),
+            deltas: csv.deltas.clone(),
             compressed_size: csv.compressed_size,
             conflicts: csv.conflicts.clone(),
             description: csv.description.clone(),
@@ -341,6 +580,7 @@ impl Package {
             installed_size: csv.installed_size,
             license: csv.license.clone(),
             linked_sources: Vec::new(),
+            raw_desc: None,
             makedepends: csv.makedepends.clone(),
             md5_sum: csv.md5_sum.clone(),
             optdepends: csv.optdepends.clone(),
@@ -353,14 +593,53 @@ impl Package {
     }
 }
 
+/// Contents of optional list field, empty if field is missing
+fn slice<T>(values: &Option<Vec<T>>) -> &[T] {
+    values.as_deref().unwrap_or_default()
+}
+
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug)]
 pub struct PackageFiles {
     #[serde(rename = "FILES")]
     pub files: Vec<String>,
 }
 
-mod date_serde {
+/// Returned when `%BUILDDATE%` of package entry is not a valid unix timestamp
+#[derive(Clone, Debug, PartialEq)]
+pub struct InvalidTimestampError {
+    value: String,
+}
+
+impl InvalidTimestampError {
+    pub(crate) fn new(value: &str) -> Self {
+        InvalidTimestampError {
+            value: value.to_owned(),
+        }
+    }
+
+    /// Invalid timestamp
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
+
+impl Display for InvalidTimestampError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "Invalid timestamp {}", &self.value)
+    }
+}
+
+impl std::error::Error for InvalidTimestampError {}
+
+/// Convert unix timestamp into date. Returns `None` if timestamp is out of range
+pub(crate) fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    Utc.timestamp_opt(value.trim().parse().ok()?, 0).single()
+}
+
+pub(crate) mod date_serde {
+    use crate::data::InvalidTimestampError;
     use chrono::{DateTime, TimeZone, Utc};
+    use serde::de::Error;
     use serde::{self, Deserialize, Deserializer, Serializer};
 
     pub fn serialize<S>(date: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error>
@@ -375,19 +654,47 @@ mod date_serde {
         D: Deserializer<'de>,
     {
         let timestamp = i64::deserialize(deserializer)?;
-        Ok(Utc.timestamp(timestamp, 0))
+        Utc.timestamp_opt(timestamp, 0)
+            .single()
+            .ok_or_else(|| D::Error::custom(InvalidTimestampError::new(&timestamp.to_string())))
+    }
+}
+
+/// [`date_serde`] for optional dates, `None` is serialized as `null`
+pub(crate) mod optional_date_serde {
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    struct Wrapper(#[serde(with = "crate::data::date_serde")] DateTime<Utc>);
+
+    pub fn serialize<S>(date: &Option<DateTime<Utc>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match date {
+            Some(date) => super::date_serde::serialize(date, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Option::<Wrapper>::deserialize(deserializer)?.map(|w| w.0))
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::{Dependency, DependencyConstraints};
+    use crate::{Dependency, DependencyConstraints, DependencyVersion, Package};
     use std::str::FromStr;
 
     #[test]
     fn parse_dependency_version_constraint_more() {
         let dep = Dependency::from_str("test>1.0").unwrap();
-        assert_eq!("test", dep.name);
+        assert_eq!("test", &*dep.name);
         let ver = dep.version.as_ref().unwrap();
         assert_eq!("1.0", ver.version);
         assert_eq!(DependencyConstraints::MoreThan, ver.constraint);
@@ -396,7 +703,7 @@ mod test {
     #[test]
     fn parse_dependency_version_constraint_less() {
         let dep = Dependency::from_str("test<1.0").unwrap();
-        assert_eq!("test", dep.name);
+        assert_eq!("test", &*dep.name);
         let ver = dep.version.as_ref().unwrap();
         assert_eq!("1.0", ver.version);
         assert_eq!(DependencyConstraints::LessThan, ver.constraint);
@@ -405,7 +712,7 @@ mod test {
     #[test]
     fn parse_dependency_version_constraint_more_or_equals() {
         let dep = Dependency::from_str("test>=1.0").unwrap();
-        assert_eq!("test", dep.name);
+        assert_eq!("test", &*dep.name);
         let ver = dep.version.as_ref().unwrap();
         assert_eq!("1.0", ver.version);
         assert_eq!(DependencyConstraints::MoreOrEqualsThan, ver.constraint);
@@ -414,7 +721,7 @@ mod test {
     #[test]
     fn parse_dependency_version_constraint_less_or_equals() {
         let dep = Dependency::from_str("test<=1.0").unwrap();
-        assert_eq!("test", dep.name);
+        assert_eq!("test", &*dep.name);
         let ver = dep.version.as_ref().unwrap();
         assert_eq!("1.0", ver.version);
         assert_eq!(DependencyConstraints::LessOrEqualsThan, ver.constraint);
@@ -423,9 +730,73 @@ mod test {
     #[test]
     fn parse_dependency_version_constraint_equals() {
         let dep = Dependency::from_str("test=1.0").unwrap();
-        assert_eq!("test", dep.name);
+        assert_eq!("test", &*dep.name);
         let ver = dep.version.as_ref().unwrap();
         assert_eq!("1.0", ver.version);
         assert_eq!(DependencyConstraints::Equals, ver.constraint);
     }
+
+    #[test]
+    fn parse_dependency_description() {
+        let dep = Dependency::from_str("python-foo>=1:2.0: for foo support").unwrap();
+        assert_eq!("python-foo", &*dep.name);
+        assert_eq!("1:2.0", dep.version.as_ref().unwrap().version);
+        assert_eq!(Some("for foo support"), dep.description.as_deref());
+        assert_eq!("python-foo>=1:2.0: for foo support", dep.to_string());
+
+        let dep = Dependency::from_str("bar: bar backend").unwrap();
+        assert_eq!("bar", &*dep.name);
+        assert!(dep.version.is_none());
+        assert_eq!(Some("bar backend"), dep.description.as_deref());
+    }
+
+    #[test]
+    fn constraints_respect_epochs() {
+        let more = DependencyVersion::from_str(">1:9.9-1").unwrap();
+        assert!(more.is_satisfied_by("2:1.0-1"));
+        assert!(!more.is_satisfied_by("9.9-2"));
+        assert!(!more.is_satisfied_by("1:9.9-1"));
+        let less = DependencyVersion::from_str("<2:0").unwrap();
+        assert!(less.is_satisfied_by("1:100.0-1"));
+        assert!(!less.is_satisfied_by("2:0.1-1"));
+        let equals = DependencyVersion::from_str("=1.0").unwrap();
+        assert!(equals.is_satisfied_by("0:1.0-3"));
+        assert!(!equals.is_satisfied_by("1:1.0-3"));
+        let at_most = DependencyVersion::from_str("<=1:1.0-1").unwrap();
+        assert!(at_most.is_satisfied_by("1:1.0-1"));
+        assert!(at_most.is_satisfied_by("5.0-1"));
+        assert!(!at_most.is_satisfied_by("1:1.0-2"));
+    }
+
+    #[test]
+    fn missing_lists_are_empty_slices() {
+        let package: Package = archlinux_repo_parser::from_str(
+            "%FILENAME%\nfoo-1.0-1-any.pkg.tar.zst\n\n%NAME%\nfoo\n\n%VERSION%\n1.0-1\n\n\
+            %CSIZE%\n1\n\n%ISIZE%\n1\n\n%MD5SUM%\nmd5\n\n%SHA256SUM%\nsha256\n\n%PGPSIG%\nsig\n\n\
+            %ARCH%\nany\n\n%BUILDDATE%\n0\n\n%PACKAGER%\nfoo\n\n%DEPENDS%\nbar>=1.0\nbaz\n\n",
+        )
+        .unwrap();
+        assert_eq!(2, package.depends().len());
+        assert_eq!("bar", &*package.depends()[0].name);
+        assert!(package.provides().is_empty());
+        assert!(package.groups().is_empty());
+        assert!(package.optdepends().is_empty());
+    }
+
+    #[test]
+    fn dependencies_are_ordered() {
+        let mut dependencies: Vec<Dependency> = ["foo>=1.10", "bar", "foo>=1.9", "foo"]
+            .iter()
+            .map(|d| Dependency::from_str(d).unwrap())
+            .collect();
+        dependencies.sort();
+        let sorted: Vec<String> = dependencies.iter().map(|d| d.to_string()).collect();
+        assert_eq!(vec!["bar", "foo", "foo>=1.9", "foo>=1.10"], sorted);
+        let unique: std::collections::HashSet<Dependency> = dependencies
+            .iter()
+            .cloned()
+            .chain(dependencies.clone())
+            .collect();
+        assert_eq!(4, unique.len());
+    }
 }
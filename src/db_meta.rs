@@ -0,0 +1,287 @@
+use crate::http::{HttpClient, HttpError, HttpResponse};
+use crate::{
+    metrics, FilesMetadata, Inner, LoadOptions, MissingSignatureError, Repository, SignatureCheck,
+};
+use chrono::{DateTime, TimeDelta, Utc};
+use std::error::Error;
+
+/// Parse HTTP date like `Wed, 21 Oct 2015 07:28:00 GMT`
+pub(crate) fn parse_http_date(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc2822(value)
+        .ok()
+        .map(|date| date.with_timezone(&Utc))
+}
+
+/// Time when response becomes stale according to `Cache-Control` or `Expires` headers.
+/// `no-cache`, `no-store` and invalid `Expires` make response stale immediately. `max-age`
+/// and `Age` above `u32::MAX` seconds are ignored. Returns `None` if server sent neither
+/// header or expiration time is out of range
+pub(crate) fn expiration(
+    response: &HttpResponse,
+    received: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    if let Some(cache_control) = response.header("cache-control") {
+        let mut max_age = None;
+        for directive in cache_control.split(',').map(|d| d.trim().to_lowercase()) {
+            if directive == "no-cache" || directive == "no-store" {
+                return Some(received);
+            }
+            if let Some(seconds) = directive.strip_prefix("max-age=") {
+                max_age = seconds.trim_matches('"').parse::<u32>().ok();
+            }
+        }
+        if let Some(max_age) = max_age {
+            let age = response
+                .header("age")
+                .and_then(|age| age.trim().parse::<u32>().ok())
+                .unwrap_or(0);
+            return TimeDelta::try_seconds(i64::from(max_age.saturating_sub(age)))
+                .and_then(|fresh| received.checked_add_signed(fresh));
+        }
+    }
+    response
+        .header("expires")
+        .map(|expires| parse_http_date(expires).unwrap_or(received))
+}
+
+impl Inner {
+    /// Remember metadata of database response
+    pub(crate) fn record_db_response(&mut self, response: &HttpResponse) {
+        self.last_modified = response.header("last-modified").and_then(parse_http_date);
+        self.etag = response.header("etag").map(str::to_owned);
+        self.db_size = response.content_length();
+        self.expires = expiration(response, Utc::now());
+    }
+
+    /// Download `.sig` file of database if it is enabled. Missing signature is an error only
+    /// if it is required by [`SigLevel`](crate::SigLevel)
+    pub(crate) async fn load_db_signature(
+        &mut self,
+        client: &dyn HttpClient,
+        db_url: &str,
+        options: &LoadOptions,
+    ) -> Result<(), MissingSignatureError> {
+        let check = match options.sig_level {
+            Some(level) => level.database.check,
+            None if options.db_signature => SignatureCheck::Optional,
+            None => SignatureCheck::Never,
+        };
+        if check == SignatureCheck::Never {
+            return Ok(());
+        }
+        let url = format!("{}.sig", db_url);
+        self.db_signature = match Inner::request(client, &url, options).await {
+            Ok(response) => metrics::observe(response, options, &url).bytes().await.ok(),
+            Err(_) => None,
+        };
+        if self.db_signature.is_none() && check == SignatureCheck::Required {
+            return Err(MissingSignatureError::new(&options.public_url(db_url)));
+        }
+        Ok(())
+    }
+}
+
+impl Repository {
+    /// Detached signature of repository database. Returns `None` if downloading it is not
+    /// enabled with [`RepositoryBuilder::db_signature`](crate::RepositoryBuilder::db_signature),
+    /// server doesn't have it or repository was not downloaded
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::RepositoryBuilder;
+    ///
+    /// let repo = RepositoryBuilder::new("mingw64", "http://repo.msys2.org/mingw/x86_64")
+    ///                 .db_signature(true)
+    ///                 .load()
+    ///                 .await?;
+    /// if let Some(signature) = repo.db_signature() {
+    ///     std::fs::write("mingw64.db.sig", signature)?;
+    /// }
+    /// ```
+    pub fn db_signature(&self) -> Option<&[u8]> {
+        self.inner.db_signature.as_deref()
+    }
+
+    /// `Last-Modified` time of repository database reported by server. Returns `None` if
+    /// server didn't report it or repository was not downloaded
+    pub fn last_modified(&self) -> Option<DateTime<Utc>> {
+        self.inner.last_modified
+    }
+
+    /// `ETag` of repository database reported by server. Returns `None` if server didn't
+    /// report it or repository was not downloaded
+    pub fn etag(&self) -> Option<&str> {
+        self.inner.etag.as_deref()
+    }
+
+    /// Size of repository database reported by server in `Content-Length` header. Returns
+    /// `None` if server didn't report it or repository was not downloaded
+    pub fn db_size(&self) -> Option<u64> {
+        self.inner.db_size
+    }
+
+    /// Time when loaded repository database becomes stale according to `Cache-Control` or
+    /// `Expires` headers sent by server. Returns `None` if server sent neither of them or
+    /// repository was not downloaded
+    pub fn expires(&self) -> Option<DateTime<Utc>> {
+        self.inner.expires
+    }
+
+    /// Returns `true` if server caching headers allow using loaded database without
+    /// revalidation, see [`Repository::expires`]
+    pub fn is_fresh(&self) -> bool {
+        self.inner
+            .expires
+            .is_some_and(|expires| Utc::now() < expires)
+    }
+
+    /// Check whether repository database on server is newer than loaded one without
+    /// downloading it. Returns `false` without sending requests while loaded database is
+    /// fresh according to server caching headers, see [`Repository::is_fresh`]. Otherwise
+    /// sends HEAD request and compares its `Last-Modified` time with the time of loaded
+    /// database or, if it is unknown, with loading time. If server doesn't report
+    /// `Last-Modified`, `ETag` values are compared. Returns `true` if neither of them is
+    /// reported, because freshness cannot be proven
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::Repository;
+    ///
+    /// let mut repo = Repository::load("mingw64", "http://repo.msys2.org/mingw/x86_64").await?;
+    /// if repo.check_remote_updated().await? {
+    ///     repo.reload().await?;
+    /// }
+    /// ```
+    pub async fn check_remote_updated(&self) -> Result<bool, Box<dyn Error>> {
+        if self.is_fresh() {
+            return Ok(false);
+        }
+        let url = self.db_url();
+        let response = self
+            .http_client
+            .head(&url)
+            .await
+            .map_err(|e| self.options.redact_error(&url, e))?;
+        if !response.is_success() {
+            let url = self.options.public_url(&url);
+            return Err(Box::new(
+                HttpError::from_response("HEAD", &url, response).await,
+            ));
+        }
+        if let Some(remote) = response.header("last-modified").and_then(parse_http_date) {
+            return Ok(remote > self.inner.last_modified.unwrap_or(self.inner.loaded_at));
+        }
+        match (response.header("etag"), &self.inner.etag) {
+            (Some(remote), Some(loaded)) => Ok(remote != loaded),
+            _ => Ok(true),
+        }
+    }
+
+    /// Reload repository only if its database on server has changed, see
+    /// [`Repository::check_remote_updated`]. Nothing is downloaded, including files database,
+    /// while database is unchanged, so frequent polling costs one HEAD request. Repository
+    /// restored from snapshot keeps database `Last-Modified` and `ETag`, so it is synced
+    /// without downloading unchanged database. Returns `true` if repository was reloaded
+    ///
+    /// Repository databases are compressed as a whole, so changed database is downloaded
+    /// completely
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::{RepositoryBuilder, RepositorySnapshot};
+    ///
+    /// let snapshot: RepositorySnapshot = serde_json::from_slice(&std::fs::read("core.json")?)?;
+    /// let mut repo = RepositoryBuilder::new("core", &snapshot.url.clone())
+    ///                     .load_snapshot(snapshot)?;
+    /// if repo.sync().await? {
+    ///     std::fs::write("core.json", serde_json::to_vec(&repo.snapshot())?)?;
+    /// }
+    /// ```
+    pub async fn sync(&mut self) -> Result<bool, Box<dyn Error>> {
+        if !self.check_remote_updated().await? {
+            return Ok(false);
+        }
+        self.reload().await?;
+        Ok(true)
+    }
+
+    /// Url of database which is loaded first
+    fn db_url(&self) -> String {
+        let file = match self.files_meta {
+            FilesMetadata::Primary => self.options.files_file(&self.name),
+            _ => self.options.db_file(&self.name),
+        };
+        format!("{}/{}", self.url, file)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::db_meta::{expiration, parse_http_date};
+    use crate::HttpResponse;
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+
+    #[test]
+    fn http_date_is_parsed() {
+        assert_eq!(
+            Some(Utc.with_ymd_and_hms(2015, 10, 21, 7, 28, 0).unwrap()),
+            parse_http_date("Wed, 21 Oct 2015 07:28:00 GMT")
+        );
+        assert_eq!(None, parse_http_date("yesterday"));
+    }
+
+    #[test]
+    fn expiration_follows_caching_headers() {
+        let response = |headers: &[(&str, &str)]| {
+            headers.iter().fold(
+                HttpResponse::new(200, None, Box::pin(futures::stream::empty())),
+                |response, (name, value)| response.with_header(name, value),
+            )
+        };
+        let now = Utc.with_ymd_and_hms(2015, 10, 21, 7, 28, 0).unwrap();
+        assert_eq!(
+            Some(now + Duration::seconds(240)),
+            expiration(
+                &response(&[("Cache-Control", "public, max-age=300"), ("Age", "60")]),
+                now
+            )
+        );
+        assert_eq!(
+            Some(now),
+            expiration(
+                &response(&[("Cache-Control", "no-cache, max-age=300")]),
+                now
+            )
+        );
+        assert_eq!(
+            Some(now + Duration::hours(1)),
+            expiration(
+                &response(&[("Expires", "Wed, 21 Oct 2015 08:28:00 GMT")]),
+                now
+            )
+        );
+        assert_eq!(Some(now), expiration(&response(&[("Expires", "0")]), now));
+        assert_eq!(
+            Some(now),
+            expiration(
+                &response(&[("Cache-Control", "max-age=60"), ("Age", "120")]),
+                now
+            )
+        );
+        assert_eq!(
+            None,
+            expiration(
+                &response(&[("Cache-Control", "max-age=99999999999999999")]),
+                now
+            )
+        );
+        assert_eq!(
+            None,
+            expiration(
+                &response(&[("Cache-Control", "max-age=60")]),
+                DateTime::<Utc>::MAX_UTC
+            )
+        );
+        assert_eq!(None, expiration(&response(&[]), now));
+    }
+}
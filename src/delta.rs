@@ -0,0 +1,112 @@
+use crate::Package;
+use serde::de::Visitor;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Returned when `%DELTAS%` line doesn't have five fields or size is not a number
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct DeltaParseError {
+    source: String,
+}
+
+impl Display for DeltaParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Cannot parse delta {}", self.source)
+    }
+}
+
+impl std::error::Error for DeltaParseError {}
+
+/// Binary delta between two versions of package from `%DELTAS%` of legacy databases. Deltas
+/// were written by repo-add before pacman 5.2 as `file md5sum size from_version to_version`
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Delta {
+    /// delta file name, like `foo-1.0-1_to_1.0-2-x86_64.delta`
+    pub file_name: String,
+    /// MD5 checksum of delta file
+    pub md5_sum: String,
+    /// delta file size
+    pub size: u64,
+    /// version of package which delta is applied to
+    pub from_version: String,
+    /// version of package which delta produces
+    pub to_version: String,
+}
+
+impl FromStr for Delta {
+    type Err = DeltaParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let error = || DeltaParseError {
+            source: value.to_owned(),
+        };
+        let fields: Vec<&str> = value.split_whitespace().collect();
+        match fields.as_slice() {
+            [file_name, md5_sum, size, from_version, to_version] => Ok(Delta {
+                file_name: file_name.to_string(),
+                md5_sum: md5_sum.to_string(),
+                size: size.parse().map_err(|_| error())?,
+                from_version: from_version.to_string(),
+                to_version: to_version.to_string(),
+            }),
+            _ => Err(error()),
+        }
+    }
+}
+
+impl Display for Delta {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {}",
+            self.file_name, self.md5_sum, self.size, self.from_version, self.to_version
+        )
+    }
+}
+
+impl<'de> Deserialize<'de> for Delta {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+        struct VisitorImpl;
+
+        impl<'de> Visitor<'de> for VisitorImpl {
+            type Value = Delta;
+
+            fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+                write!(
+                    formatter,
+                    "delta like 'file md5sum size from_version to_version'"
+                )
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Delta::from_str(v).map_err(|e| Error::custom(e.to_string()))
+            }
+        }
+
+        deserializer.deserialize_str(VisitorImpl)
+    }
+}
+
+impl Serialize for Delta {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl Package {
+    /// Deltas from `%DELTAS%` of legacy databases, empty if package has none
+    pub fn deltas(&self) -> &[Delta] {
+        self.deltas.as_deref().unwrap_or_default()
+    }
+}
@@ -0,0 +1,216 @@
+use crate::{Package, RepositorySnapshot};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Package which version differs between snapshots
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct VersionChange {
+    pub name: String,
+    pub old_version: String,
+    pub new_version: String,
+}
+
+/// Run-time dependency edge, like `foo -> glibc`. Version constraints are ignored
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct DependencyEdge {
+    /// name of dependent package
+    pub package: String,
+    /// name of dependency
+    pub dependency: String,
+}
+
+/// Changes of run-time dependency graph between snapshots
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct DependencyGraphDiff {
+    /// edges present only in newer snapshot, including edges of added packages
+    pub added_edges: Vec<DependencyEdge>,
+    /// edges present only in older snapshot, including edges of removed packages
+    pub removed_edges: Vec<DependencyEdge>,
+    /// packages present in both snapshots which dependency set changed
+    pub changed: Vec<String>,
+}
+
+/// Difference between two snapshots of the same repository, see [`RepositorySnapshot::diff`].
+/// Package lists are sorted by name
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct RepoDiff {
+    /// packages present only in newer snapshot
+    pub added: Vec<String>,
+    /// packages present only in older snapshot
+    pub removed: Vec<String>,
+    /// packages present in both snapshots with different versions
+    pub updated: Vec<VersionChange>,
+    /// dependency graph changes, computed only by
+    /// [`RepositorySnapshot::diff_with_dependencies`]
+    pub dependencies: Option<DependencyGraphDiff>,
+}
+
+impl RepositorySnapshot {
+    /// Compare snapshot with newer snapshot of the same repository
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::Repository;
+    ///
+    /// let mut repo = Repository::load("mingw64", "http://repo.msys2.org/mingw/x86_64").await?;
+    /// let before = repo.snapshot();
+    /// repo.reload().await?;
+    /// for change in before.diff(&repo.snapshot()).updated {
+    ///     println!("{}: {} -> {}", change.name, change.old_version, change.new_version);
+    /// }
+    /// ```
+    pub fn diff(&self, newer: &RepositorySnapshot) -> RepoDiff {
+        let old = by_name(self);
+        let new = by_name(newer);
+        RepoDiff {
+            added: new
+                .keys()
+                .filter(|name| !old.contains_key(*name))
+                .map(|name| name.to_string())
+                .collect(),
+            removed: old
+                .keys()
+                .filter(|name| !new.contains_key(*name))
+                .map(|name| name.to_string())
+                .collect(),
+            updated: old
+                .iter()
+                .filter_map(|(name, package)| {
+                    let newer = new.get(name)?;
+                    if newer.version == package.version {
+                        return None;
+                    }
+                    Some(VersionChange {
+                        name: name.to_string(),
+                        old_version: package.version.clone(),
+                        new_version: newer.version.clone(),
+                    })
+                })
+                .collect(),
+            dependencies: None,
+        }
+    }
+
+    /// Compare snapshot with newer snapshot like [`RepositorySnapshot::diff`] and also find
+    /// changes of run-time dependency graph, for example packages which gained new dependency
+    pub fn diff_with_dependencies(&self, newer: &RepositorySnapshot) -> RepoDiff {
+        let old = edges(self);
+        let new = edges(newer);
+        let old_packages = by_name(self);
+        let new_packages = by_name(newer);
+        let changed = old_packages
+            .iter()
+            .filter(|(name, package)| {
+                new_packages
+                    .get(*name)
+                    .is_some_and(|newer| dependencies(package) != dependencies(newer))
+            })
+            .map(|(name, _)| name.to_string())
+            .collect();
+        RepoDiff {
+            dependencies: Some(DependencyGraphDiff {
+                added_edges: new.difference(&old).cloned().collect(),
+                removed_edges: old.difference(&new).cloned().collect(),
+                changed,
+            }),
+            ..self.diff(newer)
+        }
+    }
+}
+
+fn by_name(snapshot: &RepositorySnapshot) -> BTreeMap<&str, &Package> {
+    snapshot
+        .packages
+        .iter()
+        .map(|p| (p.name.as_str(), p))
+        .collect()
+}
+
+fn dependencies(package: &Package) -> BTreeSet<&str> {
+    package.depends().iter().map(|d| &*d.name).collect()
+}
+
+fn edges(snapshot: &RepositorySnapshot) -> BTreeSet<DependencyEdge> {
+    snapshot
+        .packages
+        .iter()
+        .flat_map(|p| {
+            dependencies(p)
+                .into_iter()
+                .map(move |dependency| DependencyEdge {
+                    package: p.name.clone(),
+                    dependency: dependency.to_owned(),
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        Dependency, DependencyEdge, Package, PackageBuilder, RepositorySnapshot, VersionChange,
+    };
+    use chrono::Utc;
+    use std::str::FromStr;
+
+    fn package(name: &str, version: &str, depends: &[&str]) -> Package {
+        depends
+            .iter()
+            .fold(PackageBuilder::new(name, version, "any"), |b, d| {
+                b.depends(Dependency::from_str(d).unwrap())
+            })
+            .compressed_size(1024)
+            .sha256_sum("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+            .build()
+            .unwrap()
+    }
+
+    fn snapshot(packages: Vec<Package>) -> RepositorySnapshot {
+        RepositorySnapshot {
+            name: "test".to_owned(),
+            url: "memory://repo".to_owned(),
+            created: Utc::now(),
+            packages,
+            files: None,
+            last_modified: None,
+            etag: None,
+        }
+    }
+
+    #[test]
+    fn dependency_changes_are_found() {
+        let old = snapshot(vec![
+            package("foo", "1.0-1", &["glibc"]),
+            package("bar", "1.0-1", &["glibc"]),
+            package("old", "1.0-1", &[]),
+        ]);
+        let new = snapshot(vec![
+            package("foo", "1.0-1", &["glibc>=2.30", "curl"]),
+            package("bar", "2.0-1", &["glibc"]),
+            package("new", "1.0-1", &["foo"]),
+        ]);
+        let diff = old.diff(&new);
+        assert_eq!(vec!["new"], diff.added);
+        assert_eq!(vec!["old"], diff.removed);
+        assert_eq!(
+            vec![VersionChange {
+                name: "bar".to_owned(),
+                old_version: "1.0-1".to_owned(),
+                new_version: "2.0-1".to_owned(),
+            }],
+            diff.updated
+        );
+        assert_eq!(None, diff.dependencies);
+
+        let edge = |package: &str, dependency: &str| DependencyEdge {
+            package: package.to_owned(),
+            dependency: dependency.to_owned(),
+        };
+        let dependencies = old.diff_with_dependencies(&new).dependencies.unwrap();
+        assert_eq!(
+            vec![edge("foo", "curl"), edge("new", "foo")],
+            dependencies.added_edges
+        );
+        assert!(dependencies.removed_edges.is_empty());
+        assert_eq!(vec!["foo"], dependencies.changed);
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{HttpClient, HttpError};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Returned when repository database cannot be chosen from directory listing
+#[derive(Clone, Debug, PartialEq)]
+pub enum DiscoveryError {
+    /// Listing has no repository databases
+    NotFound,
+    /// Listing has several repository databases, name should be set explicitly
+    Ambiguous(Vec<String>),
+}
+
+impl Display for DiscoveryError {
+    fn fmt(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        match self {
+            DiscoveryError::NotFound => write!(formatter, "Repository database not found"),
+            DiscoveryError::Ambiguous(names) => write!(
+                formatter,
+                "Found several repository databases: {}",
+                names.join(", ")
+            ),
+        }
+    }
+}
+
+impl Error for DiscoveryError {}
+
+/// Download directory listing of repository url and return names of repository databases
+/// in it. HTML listings and JSON listings (like nginx `autoindex_format json`) are supported
+///
+/// # Example
+/// ```ignore
+/// use archlinux_repo::{discover_databases, ReqwestClient};
+///
+/// let names = discover_databases(&ReqwestClient::default(), "http://repo.msys2.org/mingw/x86_64").await?;
+/// ```
+pub async fn discover_databases(
+    client: &dyn HttpClient,
+    url: &str,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let listing_url = format!("{}/", url.trim_end_matches('/'));
+    let response = client.get(&listing_url).await?;
+    if !response.is_success() {
+        return Err(Box::new(
+            HttpError::from_response("GET", &listing_url, response).await,
+        ));
+    }
+    let listing = String::from_utf8_lossy(&response.bytes().await?).into_owned();
+    Ok(database_names(&listing))
+}
+
+/// Find database names in directory listing preserving their order
+fn database_names(listing: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for file in file_names(listing) {
+        let file = file.split(['?', '#']).next().unwrap_or_default();
+        let file = file.rsplit('/').next().unwrap_or_default();
+        let name = file
+            .strip_suffix(".db.tar.gz")
+            .or_else(|| file.strip_suffix(".db"));
+        if let Some(name) = name {
+            if !name.is_empty() && !names.iter().any(|n| n == name) {
+                names.push(name.to_owned());
+            }
+        }
+    }
+    names
+}
+
+fn file_names(listing: &str) -> Vec<String> {
+    if let Ok(serde_json::Value::Array(entries)) = serde_json::from_str(listing) {
+        return entries
+            .iter()
+            .filter_map(|entry| entry.get("name")?.as_str())
+            .map(|name| name.to_owned())
+            .collect();
+    }
+    listing
+        .split("href=")
+        .skip(1)
+        .filter_map(|attribute| {
+            let quote = attribute.chars().next()?;
+            if quote != '"' && quote != '\'' {
+                return None;
+            }
+            attribute[1..].split(quote).next()
+        })
+        .map(|link| link.to_owned())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::discovery::database_names;
+
+    #[test]
+    fn names_from_html_listing() {
+        let listing = r#"<html><body><pre>
+            <a href="../">../</a>
+            <a href="mingw64.db">mingw64.db</a>
+            <a href="mingw64.db.sig">mingw64.db.sig</a>
+            <a href='mingw64.db.tar.gz'>mingw64.db.tar.gz</a>
+            <a href="mingw64.files">mingw64.files</a>
+            <a href="/mingw/x86_64/ucrt64.db.tar.gz">ucrt64.db.tar.gz</a>
+        </pre></body></html>"#;
+        assert_eq!(vec!["mingw64", "ucrt64"], database_names(listing));
+    }
+
+    #[test]
+    fn names_from_json_listing() {
+        let listing = r#"[
+            {"name": "core.db", "type": "file"},
+            {"name": "core.files", "type": "file"},
+            {"name": "os", "type": "directory"}
+        ]"#;
+        assert_eq!(vec!["core"], database_names(listing));
+    }
+}
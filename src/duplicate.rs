@@ -0,0 +1,70 @@
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+
+/// What to do when package name is already registered. Split packages sharing base name
+/// are not duplicates
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum DuplicateStrategy {
+    /// Fail loading with [`DuplicatePackageError`]
+    Error,
+    /// Keep package which was registered first
+    KeepFirst,
+    /// Replace registered package with new one
+    KeepLast,
+    /// Keep package with greater version, see [`crate::vercmp`]
+    KeepNewest,
+}
+
+/// Key under which packages collided
+#[derive(Serialize, Clone, Copy, Eq, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateKind {
+    /// package name
+    Name,
+}
+
+impl Display for DuplicateKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            DuplicateKind::Name => "name",
+        })
+    }
+}
+
+/// Returned when packages collide and [`DuplicateStrategy::Error`] is used
+#[derive(Clone, Debug, PartialEq)]
+pub struct DuplicatePackageError {
+    kind: DuplicateKind,
+    key: String,
+}
+
+impl DuplicatePackageError {
+    pub(crate) fn new(kind: DuplicateKind, key: &str) -> Self {
+        DuplicatePackageError {
+            kind,
+            key: key.to_owned(),
+        }
+    }
+
+    /// Key under which packages collided
+    pub fn kind(&self) -> DuplicateKind {
+        self.kind
+    }
+
+    /// Duplicated name
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+}
+
+impl Display for DuplicatePackageError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            formatter,
+            "Found several packages with {} {}",
+            self.kind, &self.key
+        )
+    }
+}
+
+impl std::error::Error for DuplicatePackageError {}
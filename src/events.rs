@@ -0,0 +1,67 @@
+use crate::{LoadWarning, Progress, ProgressListener};
+
+/// Receives typed events of repository loading, reloading and package downloads. All methods
+/// do nothing by default, so implementations override only events they need. Unlike
+/// [`ProgressListener`], [`EventListener::on_entry_parsed`] is called from archive reader
+/// threads, so methods must not block. [`ProgressListener`] implements this trait, so existing
+/// progress closures can be passed to
+/// [`RepositoryBuilder::event_listener`](crate::RepositoryBuilder::event_listener)
+///
+/// # Example
+/// ```ignore
+/// use archlinux_repo::{EventListener, LoadWarning, RepositoryBuilder};
+///
+/// struct Log;
+///
+/// impl EventListener for Log {
+///     fn on_download_start(&self, url: &str) {
+///         println!("downloading {}", url);
+///     }
+///
+///     fn on_warning(&self, warning: &LoadWarning) {
+///         println!("warning: {}", warning);
+///     }
+/// }
+///
+/// let repo = RepositoryBuilder::new("mingw64", "http://repo.msys2.org/mingw/x86_64")
+///                 .event_listener(Log)
+///                 .load()
+///                 .await?;
+/// ```
+pub trait EventListener: Send + Sync {
+    /// Called before database, files metadata, package or signature is requested
+    fn on_download_start(&self, url: &str) {
+        let _ = url;
+    }
+
+    /// Called with the same progress as [`ProgressListener`]
+    fn on_progress(&self, progress: &Progress) {
+        let _ = progress;
+    }
+
+    /// Called after archive entry, like `foo-1.0-1/desc`, is parsed
+    fn on_entry_parsed(&self, path: &str) {
+        let _ = path;
+    }
+
+    /// Called for every non-fatal problem after database or files metadata is loaded
+    fn on_warning(&self, warning: &LoadWarning) {
+        let _ = warning;
+    }
+
+    /// Called when request to url is repeated, for example on next mirror
+    fn on_retry(&self, url: &str) {
+        let _ = url;
+    }
+
+    /// Called when repository is loaded or reloaded
+    fn on_complete(&self, repository: &str) {
+        let _ = repository;
+    }
+}
+
+impl EventListener for ProgressListener {
+    fn on_progress(&self, progress: &Progress) {
+        self(progress.clone())
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{Package, Repository};
+use serde::{Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::io::Write;
+
+#[derive(Serialize)]
+struct RepositoryView<'a> {
+    name: &'a str,
+    url: &'a str,
+    packages: Vec<&'a Package>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    files: Option<BTreeMap<&'a str, &'a Vec<String>>>,
+}
+
+/// Serializes repository name, url, all packages and files metadata, if it is loaded
+impl Serialize for Repository {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let files = self.inner.package_files.get().map(|files| {
+            files
+                .iter()
+                .map(|(name, files)| (name.as_str(), &files.files))
+                .collect()
+        });
+        RepositoryView {
+            name: &self.name,
+            url: &self.url,
+            packages: self.into_iter().collect(),
+            files,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl Repository {
+    /// Write repository as JSON. Package fields are named like in repository database
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::Repository;
+    ///
+    /// let repo = Repository::load("mingw64", "http://repo.msys2.org/mingw/x86_64").await?;
+    /// repo.to_json_writer(std::fs::File::create("mingw64.json")?)?;
+    /// ```
+    pub fn to_json_writer<W: Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        serde_json::to_writer(writer, self)
+    }
+}
@@ -0,0 +1,183 @@
+use crate::http::{HttpClient, HttpResponse};
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::error::Error;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Size of chunks in which replayed body is returned
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Recorded response head, stored next to response body
+#[derive(Serialize, Deserialize)]
+struct FixtureMeta {
+    method: String,
+    url: String,
+    status: u16,
+    content_length: Option<u64>,
+    #[serde(default)]
+    headers: Vec<(String, String)>,
+}
+
+enum Mode {
+    Record(Arc<dyn HttpClient>),
+    Replay,
+}
+
+/// [`HttpClient`] which records responses of another client into fixture directory, or replays
+/// recorded responses without network access. Every request is stored as `<hash>.json` file
+/// with response status and `<hash>.body` file with response body, where hash is computed
+/// from method, url and requested range. Replaying request which wasn't recorded fails
+///
+/// # Example
+/// ```ignore
+/// use archlinux_repo::{FixtureClient, ReqwestClient, RepositoryBuilder};
+///
+/// let client = if std::env::var("RECORD_FIXTURES").is_ok() {
+///     FixtureClient::record("tests/fixtures", ReqwestClient::default())
+/// } else {
+///     FixtureClient::replay("tests/fixtures")
+/// };
+/// let repo = RepositoryBuilder::new("mingw64", "http://repo.msys2.org/mingw/x86_64")
+///                 .http_client(client)
+///                 .load()
+///                 .await?;
+/// ```
+pub struct FixtureClient {
+    dir: PathBuf,
+    mode: Mode,
+}
+
+impl FixtureClient {
+    /// Send requests with `client` and record responses into `dir`. Directory is created if
+    /// it doesn't exist
+    pub fn record<P, C>(dir: P, client: C) -> Self
+    where
+        P: Into<PathBuf>,
+        C: HttpClient + 'static,
+    {
+        FixtureClient {
+            dir: dir.into(),
+            mode: Mode::Record(Arc::new(client)),
+        }
+    }
+
+    /// Answer requests with responses recorded into `dir`
+    pub fn replay<P: Into<PathBuf>>(dir: P) -> Self {
+        FixtureClient {
+            dir: dir.into(),
+            mode: Mode::Replay,
+        }
+    }
+
+    /// Fixture directory
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn request<'a>(
+        &'a self,
+        method: &'static str,
+        url: &'a str,
+        range: Option<Range<u64>>,
+    ) -> BoxFuture<'a, Result<HttpResponse, Box<dyn Error>>> {
+        Box::pin(async move {
+            let mut hasher = Sha256::new();
+            hasher.update(method);
+            hasher.update(" ");
+            hasher.update(url);
+            if let Some(range) = &range {
+                hasher.update(format!(" {}-{}", range.start, range.end));
+            }
+            let path = self.dir.join(format!("{:x}", hasher.finalize()));
+            let (meta, body) = match &self.mode {
+                Mode::Record(client) => {
+                    let response = match (method, range) {
+                        ("HEAD", _) => client.head(url).await?,
+                        (_, Some(range)) => client.get_range(url, range).await?,
+                        (_, None) => client.get(url).await?,
+                    };
+                    let meta = FixtureMeta {
+                        method: method.to_owned(),
+                        url: url.to_owned(),
+                        status: response.status(),
+                        content_length: response.content_length(),
+                        headers: response.headers().to_vec(),
+                    };
+                    let body = response.bytes().await?;
+                    std::fs::create_dir_all(&self.dir)?;
+                    std::fs::write(path.with_extension("json"), serde_json::to_vec(&meta)?)?;
+                    std::fs::write(path.with_extension("body"), &body)?;
+                    (meta, body)
+                }
+                Mode::Replay => {
+                    let meta: FixtureMeta = match std::fs::read(path.with_extension("json")) {
+                        Ok(meta) => serde_json::from_slice(&meta)?,
+                        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                            return Err(format!("{} {} is not recorded", method, url).into())
+                        }
+                        Err(e) => return Err(e.into()),
+                    };
+                    let body = std::fs::read(path.with_extension("body"))?;
+                    (meta, body)
+                }
+            };
+            let chunks: Vec<_> = body
+                .chunks(CHUNK_SIZE)
+                .map(|chunk| Ok(chunk.to_vec()))
+                .collect();
+            Ok(HttpResponse::new(
+                meta.status,
+                meta.content_length,
+                Box::pin(futures::stream::iter(chunks)),
+            )
+            .with_headers(meta.headers))
+        })
+    }
+}
+
+impl HttpClient for FixtureClient {
+    fn get<'a>(&'a self, url: &'a str) -> BoxFuture<'a, Result<HttpResponse, Box<dyn Error>>> {
+        self.request("GET", url, None)
+    }
+
+    fn get_range<'a>(
+        &'a self,
+        url: &'a str,
+        range: Range<u64>,
+    ) -> BoxFuture<'a, Result<HttpResponse, Box<dyn Error>>> {
+        self.request("GET", url, Some(range))
+    }
+
+    fn head<'a>(&'a self, url: &'a str) -> BoxFuture<'a, Result<HttpResponse, Box<dyn Error>>> {
+        self.request("HEAD", url, None)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{FixtureClient, HttpClient, MemoryClient};
+
+    #[tokio::test]
+    async fn responses_are_replayed() {
+        let dir = std::env::temp_dir().join("archlinux-repo-fixtures");
+        let _ = std::fs::remove_dir_all(&dir);
+        let memory = MemoryClient::new().file("memory://repo/test.db", "database");
+        let recorder = FixtureClient::record(&dir, memory);
+        let recorded = recorder.get("memory://repo/test.db").await.unwrap();
+        assert_eq!(b"database".to_vec(), recorded.bytes().await.unwrap());
+        let missing = recorder.get("memory://repo/missing").await.unwrap();
+        assert_eq!(404, missing.status());
+
+        let replayer = FixtureClient::replay(&dir);
+        let replayed = replayer.get("memory://repo/test.db").await.unwrap();
+        assert_eq!(200, replayed.status());
+        assert_eq!(Some(8), replayed.content_length());
+        assert_eq!(b"database".to_vec(), replayed.bytes().await.unwrap());
+        let missing = replayer.get("memory://repo/missing").await.unwrap();
+        assert_eq!(404, missing.status());
+        assert!(replayer.head("memory://repo/test.db").await.is_err());
+    }
+}
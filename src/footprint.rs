@@ -0,0 +1,155 @@
+use crate::data::PackageFiles;
+use crate::{Delta, Dependency, IndexEntry, Inner, Package, Repository};
+use std::mem::size_of;
+use std::sync::Arc;
+
+/// Approximate memory used by repository, in bytes. See [`Repository::memory_footprint`]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+pub struct MemoryFootprint {
+    /// packages, including shared strings
+    pub packages: usize,
+    /// lookup indexes
+    pub indexes: usize,
+    /// files metadata, `0` if it is not loaded
+    pub files: usize,
+}
+
+impl MemoryFootprint {
+    /// Total memory used by repository
+    pub fn total(&self) -> usize {
+        self.packages + self.indexes + self.files
+    }
+}
+
+fn strings(values: &Option<Vec<String>>) -> usize {
+    values.as_ref().map_or(0, |values| {
+        values.capacity() * size_of::<String>() + values.iter().map(|v| v.capacity()).sum::<usize>()
+    })
+}
+
+/// Vector of pooled strings, strings are counted with pool
+fn pooled(values: &Option<Vec<Arc<str>>>) -> usize {
+    values
+        .as_ref()
+        .map_or(0, |values| values.capacity() * size_of::<Arc<str>>())
+}
+
+fn dependencies(values: &Option<Vec<Dependency>>) -> usize {
+    values.as_ref().map_or(0, |values| {
+        values.capacity() * size_of::<Dependency>()
+            + values
+                .iter()
+                .map(|d| {
+                    d.version.as_ref().map_or(0, |v| v.version.capacity())
+                        + d.description.as_ref().map_or(0, String::capacity)
+                })
+                .sum::<usize>()
+    })
+}
+
+fn deltas(values: &Option<Vec<Delta>>) -> usize {
+    values.as_ref().map_or(0, |values| {
+        values.capacity() * size_of::<Delta>()
+            + values
+                .iter()
+                .map(|d| {
+                    d.file_name.capacity()
+                        + d.md5_sum.capacity()
+                        + d.from_version.capacity()
+                        + d.to_version.capacity()
+                })
+                .sum::<usize>()
+    })
+}
+
+fn package(package: &Package) -> usize {
+    size_of::<Package>()
+        + package.file_name.capacity()
+        + package.name.capacity()
+        + package.base.as_ref().map_or(0, String::capacity)
+        + package.version.capacity()
+        + package.description.as_ref().map_or(0, String::capacity)
+        + pooled(&package.groups)
+        + package.md5_sum.capacity()
+        + package.sha256_sum.capacity()
+        + package.pgp_signature.capacity()
+        + package.home_url.as_ref().map_or(0, String::capacity)
+        + pooled(&package.license)
+        + strings(&package.replaces)
+        + strings(&package.conflicts)
+        + strings(&package.provides)
+        + dependencies(&package.depends)
+        + dependencies(&package.optdepends)
+        + dependencies(&package.makedepends)
+        + dependencies(&package.checkdepends)
+        + deltas(&package.deltas)
+        + package.linked_sources.capacity() * size_of::<Arc<Package>>()
+        + package.raw_desc.as_ref().map_or(0, |desc| desc.len())
+}
+
+/// Memory of hash map table without keys and values contents
+pub(crate) fn table<K, V>(capacity: usize) -> usize {
+    // hashbrown stores one control byte per bucket
+    capacity * (size_of::<K>() + size_of::<V>() + 1)
+}
+
+fn files(files: &PackageFiles) -> usize {
+    files.files.capacity() * size_of::<String>()
+        + files.files.iter().map(|f| f.capacity()).sum::<usize>()
+}
+
+impl Inner {
+    fn memory_footprint(&self) -> MemoryFootprint {
+        let packages = self.packages.capacity() * size_of::<Arc<Package>>()
+            + self.packages.iter().map(|p| package(p)).sum::<usize>()
+            + self.strings.memory_footprint();
+        let entries = table::<String, IndexEntry>(self.index.capacity())
+            + self
+                .index
+                .iter()
+                .map(|(key, entry)| {
+                    key.capacity() + entry.base.capacity() * size_of::<Arc<Package>>()
+                })
+                .sum::<usize>();
+        let synthetic = table::<String, ()>(self.synthetic.capacity())
+            + self.synthetic.iter().map(String::capacity).sum::<usize>();
+        let sorted = self
+            .sorted
+            .get()
+            .map_or(0, |s| s.capacity() * size_of::<Arc<Package>>());
+        let aliases = table::<String, Arc<Package>>(self.aliases.capacity())
+            + self.aliases.keys().map(String::capacity).sum::<usize>();
+        let relations = self
+            .relations
+            .get()
+            .map_or(0, |relations| relations.memory_footprint());
+        let files = self.package_files.get().map_or(0, |package_files| {
+            table::<String, PackageFiles>(package_files.capacity())
+                + package_files
+                    .iter()
+                    .map(|(name, f)| name.capacity() + files(f))
+                    .sum::<usize>()
+        });
+        MemoryFootprint {
+            packages,
+            indexes: entries + synthetic + sorted + aliases + relations,
+            files,
+        }
+    }
+}
+
+impl Repository {
+    /// Approximate memory used by packages, lookup indexes and files metadata. Allocator
+    /// overhead is not included
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::Repository;
+    ///
+    /// let repo = Repository::load("mingw64", "http://repo.msys2.org/mingw/x86_64").await?;
+    /// println!("{} MiB", repo.memory_footprint().total() / 1024 / 1024);
+    /// ```
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        self.inner.memory_footprint()
+    }
+}
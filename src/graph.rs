@@ -0,0 +1,254 @@
+use crate::{Package, Repository};
+use rustc_hash::FxHashMap;
+use std::collections::{HashMap, VecDeque};
+use std::io::Write;
+
+/// Dependency graph metrics of package, see [`Repository::dependency_metrics`]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+pub struct DependencyMetrics {
+    /// length of longest chain of dependencies, where each dependency is reached by shortest
+    /// path. `0` if package has no dependencies
+    pub depth: usize,
+    /// number of packages this package depends on, directly or transitively
+    pub dependencies: usize,
+    /// number of packages which depend on this package, directly or transitively
+    pub impact: usize,
+}
+
+/// Run-time dependency graph of repository packages. Nodes are indexes of packages in
+/// repository order, dependencies are resolved by package name, then by provided names.
+/// Dependencies which cannot be resolved inside repository are skipped
+pub(crate) struct DependencyGraph<'a> {
+    pub(crate) packages: Vec<&'a Package>,
+    pub(crate) dependencies: Vec<Vec<usize>>,
+    pub(crate) dependents: Vec<Vec<usize>>,
+}
+
+impl<'a> DependencyGraph<'a> {
+    pub(crate) fn new(repo: &'a Repository) -> Self {
+        let packages: Vec<&Package> = repo.into_iter().collect();
+        let mut names: FxHashMap<&str, usize> = FxHashMap::default();
+        for (i, package) in packages.iter().enumerate() {
+            for provision in package.provides.iter().flatten() {
+                let name = provision.split('=').next().unwrap_or(provision);
+                names.entry(name).or_insert(i);
+            }
+        }
+        // real packages win over providers
+        for (i, package) in packages.iter().enumerate() {
+            names.insert(&package.name, i);
+        }
+        let mut dependencies = vec![Vec::new(); packages.len()];
+        let mut dependents = vec![Vec::new(); packages.len()];
+        for (i, package) in packages.iter().enumerate() {
+            for dependency in package.depends.iter().flatten() {
+                match names.get(&*dependency.name) {
+                    Some(&j) if j != i && !dependencies[i].contains(&j) => {
+                        dependencies[i].push(j);
+                        dependents[j].push(i);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        DependencyGraph {
+            packages,
+            dependencies,
+            dependents,
+        }
+    }
+
+    /// Dependency edges from package to its dependency between included nodes
+    fn edges<'b>(&'b self, included: &'b [bool]) -> impl Iterator<Item = (usize, usize)> + 'b {
+        self.dependencies
+            .iter()
+            .enumerate()
+            .filter(move |(i, _)| included[*i])
+            .flat_map(move |(i, dependencies)| {
+                dependencies
+                    .iter()
+                    .filter(move |j| included[**j])
+                    .map(move |&j| (i, j))
+            })
+    }
+
+    /// Breadth-first walk from node. Returns number of reached nodes, excluding start node,
+    /// and greatest distance to them
+    fn walk(edges: &[Vec<usize>], start: usize, visited: &mut [bool]) -> (usize, usize) {
+        visited.iter_mut().for_each(|v| *v = false);
+        visited[start] = true;
+        let mut queue = VecDeque::new();
+        queue.push_back((start, 0));
+        let (mut reached, mut depth) = (0, 0);
+        while let Some((node, distance)) = queue.pop_front() {
+            depth = depth.max(distance);
+            for &next in &edges[node] {
+                if !visited[next] {
+                    visited[next] = true;
+                    reached += 1;
+                    queue.push_back((next, distance + 1));
+                }
+            }
+        }
+        (reached, depth)
+    }
+}
+
+impl Repository {
+    /// Compute dependency metrics of every package over run-time dependency graph. Result is
+    /// keyed by package name. Dependencies are resolved by name and then by provided names,
+    /// dependencies which are not in repository are skipped
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::Repository;
+    ///
+    /// let repo = Repository::load("mingw64", "http://repo.msys2.org/mingw/x86_64").await?;
+    /// let metrics = repo.dependency_metrics();
+    /// let mut packages: Vec<_> = metrics.iter().collect();
+    /// packages.sort_by_key(|(_, m)| std::cmp::Reverse(m.impact));
+    /// println!("Most used package: {}", packages[0].0);
+    /// ```
+    pub fn dependency_metrics(&self) -> HashMap<String, DependencyMetrics> {
+        let graph = DependencyGraph::new(self);
+        let mut visited = vec![false; graph.packages.len()];
+        let mut metrics = HashMap::with_capacity(graph.packages.len());
+        for (i, package) in graph.packages.iter().enumerate() {
+            let (dependencies, depth) = DependencyGraph::walk(&graph.dependencies, i, &mut visited);
+            let (impact, _) = DependencyGraph::walk(&graph.dependents, i, &mut visited);
+            metrics.insert(
+                package.name.clone(),
+                DependencyMetrics {
+                    depth,
+                    dependencies,
+                    impact,
+                },
+            );
+        }
+        metrics
+    }
+
+    /// Write run-time dependency graph in GraphML format, which is supported by yEd and
+    /// Gephi. Only packages accepted by `filter` and dependencies between them are written.
+    /// Nodes have package name as label and version and installed size as attributes
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::Repository;
+    ///
+    /// let repo = Repository::load("mingw64", "http://repo.msys2.org/mingw/x86_64").await?;
+    /// let file = std::fs::File::create("mingw64.graphml")?;
+    /// repo.write_graphml(std::io::BufWriter::new(file), |p| p.name.contains("python"))?;
+    /// ```
+    pub fn write_graphml<W, F>(&self, mut writer: W, filter: F) -> std::io::Result<()>
+    where
+        W: Write,
+        F: Fn(&Package) -> bool,
+    {
+        let graph = DependencyGraph::new(self);
+        let included: Vec<bool> = graph.packages.iter().map(|p| filter(p)).collect();
+        writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            writer,
+            r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+        )?;
+        writeln!(
+            writer,
+            r#"  <key id="label" for="node" attr.name="label" attr.type="string"/>"#
+        )?;
+        writeln!(
+            writer,
+            r#"  <key id="version" for="node" attr.name="version" attr.type="string"/>"#
+        )?;
+        writeln!(
+            writer,
+            r#"  <key id="installed_size" for="node" attr.name="installed_size" attr.type="long"/>"#
+        )?;
+        writeln!(
+            writer,
+            r#"  <graph id="{}" edgedefault="directed">"#,
+            escape(&self.name)
+        )?;
+        for (i, package) in graph.packages.iter().enumerate() {
+            if !included[i] {
+                continue;
+            }
+            writeln!(
+                writer,
+                r#"    <node id="n{}"><data key="label">{}</data><data key="version">{}</data><data key="installed_size">{}</data></node>"#,
+                i,
+                escape(&package.name),
+                escape(&package.version),
+                package.installed_size
+            )?;
+        }
+        for (i, j) in graph.edges(&included) {
+            writeln!(writer, r#"    <edge source="n{}" target="n{}"/>"#, i, j)?;
+        }
+        writeln!(writer, "  </graph>")?;
+        writeln!(writer, "</graphml>")
+    }
+
+    /// Write run-time dependency graph in GEXF format, which is native format of Gephi. Only
+    /// packages accepted by `filter` and dependencies between them are written. Nodes have
+    /// package name as label and version and installed size as attributes
+    pub fn write_gexf<W, F>(&self, mut writer: W, filter: F) -> std::io::Result<()>
+    where
+        W: Write,
+        F: Fn(&Package) -> bool,
+    {
+        let graph = DependencyGraph::new(self);
+        let included: Vec<bool> = graph.packages.iter().map(|p| filter(p)).collect();
+        writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            writer,
+            r#"<gexf xmlns="http://gexf.net/1.3" version="1.3">"#
+        )?;
+        writeln!(writer, r#"  <graph defaultedgetype="directed">"#)?;
+        writeln!(writer, r#"    <attributes class="node">"#)?;
+        writeln!(
+            writer,
+            r#"      <attribute id="version" title="version" type="string"/>"#
+        )?;
+        writeln!(
+            writer,
+            r#"      <attribute id="installed_size" title="installed_size" type="long"/>"#
+        )?;
+        writeln!(writer, "    </attributes>")?;
+        writeln!(writer, "    <nodes>")?;
+        for (i, package) in graph.packages.iter().enumerate() {
+            if !included[i] {
+                continue;
+            }
+            writeln!(
+                writer,
+                r#"      <node id="{}" label="{}"><attvalues><attvalue for="version" value="{}"/><attvalue for="installed_size" value="{}"/></attvalues></node>"#,
+                i,
+                escape(&package.name),
+                escape(&package.version),
+                package.installed_size
+            )?;
+        }
+        writeln!(writer, "    </nodes>")?;
+        writeln!(writer, "    <edges>")?;
+        for (id, (i, j)) in graph.edges(&included).enumerate() {
+            writeln!(
+                writer,
+                r#"      <edge id="{}" source="{}" target="{}"/>"#,
+                id, i, j
+            )?;
+        }
+        writeln!(writer, "    </edges>")?;
+        writeln!(writer, "  </graph>")?;
+        writeln!(writer, "</gexf>")
+    }
+}
+
+/// Escape text for XML attribute or element
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
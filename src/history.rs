@@ -0,0 +1,74 @@
+use crate::{Inner, Repository};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+/// Package version seen by repository, see [`Repository::package_history`]
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct VersionRecord {
+    pub version: String,
+    /// load time of first database which had this version
+    pub first_seen: DateTime<Utc>,
+    /// load time of last database which had this version
+    pub last_seen: DateTime<Utc>,
+}
+
+/// Versions of packages by package name, oldest first
+#[derive(Default)]
+pub(crate) struct History {
+    packages: HashMap<String, Vec<VersionRecord>>,
+}
+
+impl History {
+    /// Record versions of loaded packages. Base packages created for VCS packages are skipped
+    pub(crate) fn record(&mut self, inner: &Inner) {
+        for package in inner
+            .packages
+            .iter()
+            .filter(|p| !inner.synthetic.contains(&p.name))
+        {
+            let records = self.packages.entry(package.name.clone()).or_default();
+            match records.last_mut() {
+                Some(last) if last.version == package.version => last.last_seen = inner.loaded_at,
+                _ => records.push(VersionRecord {
+                    version: package.version.clone(),
+                    first_seen: inner.loaded_at,
+                    last_seen: inner.loaded_at,
+                }),
+            }
+        }
+    }
+}
+
+impl Repository {
+    /// Get versions of package seen by this repository across [`Repository::reload`] calls,
+    /// oldest first. Returns `None` if history tracking is disabled (see
+    /// [`RepositoryBuilder::track_history`](crate::RepositoryBuilder::track_history)) or
+    /// package was never seen
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::RepositoryBuilder;
+    ///
+    /// let mut repo = RepositoryBuilder::new("mingw64", "http://repo.msys2.org/mingw/x86_64")
+    ///                 .track_history(true)
+    ///                 .load()
+    ///                 .await?;
+    /// repo.reload().await?;
+    /// for record in repo.package_history("mingw-w64-x86_64-gtk3").unwrap() {
+    ///     println!("{} since {}", record.version, record.first_seen);
+    /// }
+    /// ```
+    pub fn package_history(&self, name: &str) -> Option<&[VersionRecord]> {
+        self.history
+            .as_ref()?
+            .packages
+            .get(name)
+            .map(|v| v.as_slice())
+    }
+
+    /// Get time when package version was first seen, so when package last changed. Returns
+    /// `None` if history tracking is disabled or package was never seen
+    pub fn last_changed(&self, name: &str) -> Option<DateTime<Utc>> {
+        self.package_history(name)?.last().map(|r| r.first_seen)
+    }
+}
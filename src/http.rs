@@ -0,0 +1,546 @@
+use futures::future::BoxFuture;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use std::error::Error;
+use std::fmt::Display;
+use std::net::IpAddr;
+#[cfg(feature = "reqwest-client")]
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::ops::Range;
+
+/// Maximum number of error body bytes kept in [`HttpError`]
+const ERROR_BODY_PREFIX: usize = 256;
+
+/// Non-successful response returned by server
+#[derive(Clone, Debug, PartialEq)]
+pub struct HttpError {
+    status: u16,
+    method: &'static str,
+    url: String,
+    retry_after: Option<String>,
+    body: Vec<u8>,
+}
+
+impl HttpError {
+    /// Create error from response to `method` request to `url`. Reads first bytes of response
+    /// body, read failures are ignored
+    pub(crate) async fn from_response(
+        method: &'static str,
+        url: &str,
+        mut response: HttpResponse,
+    ) -> Self {
+        let mut body = Vec::new();
+        while body.len() < ERROR_BODY_PREFIX {
+            match response.chunk().await {
+                Ok(Some(chunk)) => body.extend_from_slice(&chunk),
+                _ => break,
+            }
+        }
+        body.truncate(ERROR_BODY_PREFIX);
+        HttpError {
+            status: response.status(),
+            method,
+            url: url.to_owned(),
+            retry_after: response.header("retry-after").map(str::to_owned),
+            body,
+        }
+    }
+
+    /// Response status code
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    /// Request method
+    pub fn method(&self) -> &str {
+        self.method
+    }
+
+    /// Requested url
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// `Retry-After` header value, either delay in seconds or HTTP date
+    pub fn retry_after(&self) -> Option<&str> {
+        self.retry_after.as_deref()
+    }
+
+    /// First bytes of response body
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+}
+
+impl Display for HttpError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            formatter,
+            "Server returned {} status for {} {}",
+            self.status, self.method, self.url
+        )?;
+        if let Some(retry_after) = &self.retry_after {
+            write!(formatter, ", retry after {}", retry_after)?;
+        }
+        let body = String::from_utf8_lossy(&self.body);
+        let body = body.trim();
+        if !body.is_empty() {
+            write!(formatter, ": {}", body)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for HttpError {}
+
+/// Response body chunks
+pub type HttpBody = BoxStream<'static, Result<Vec<u8>, Box<dyn Error + Send + Sync>>>;
+
+/// HTTP response returned by [`HttpClient`]
+pub struct HttpResponse {
+    status: u16,
+    content_length: Option<u64>,
+    headers: Vec<(String, String)>,
+    body: HttpBody,
+}
+
+impl HttpResponse {
+    /// Create new response from status code, `Content-Length` header value and body stream
+    pub fn new(status: u16, content_length: Option<u64>, body: HttpBody) -> Self {
+        HttpResponse {
+            status,
+            content_length,
+            headers: Vec::new(),
+            body,
+        }
+    }
+
+    /// Add response header. Headers are used to read metadata like `Last-Modified`
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Add `(name, value)` response headers
+    pub(crate) fn with_headers(mut self, headers: Vec<(String, String)>) -> Self {
+        self.headers.extend(headers);
+        self
+    }
+
+    /// Value of first header with name, compared case-insensitively
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// All response headers
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    /// Response status code
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    /// Returns `true` if status code is 2xx
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// Response body size, if known
+    pub fn content_length(&self) -> Option<u64> {
+        self.content_length
+    }
+
+    /// Read next body chunk. Returns `None` when body is fully read
+    pub async fn chunk(&mut self) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+        self.body
+            .next()
+            .await
+            .transpose()
+            .map_err(|e| e as Box<dyn Error>)
+    }
+
+    /// Call `f` with size of every body chunk when it is read
+    pub(crate) fn on_chunk<F>(mut self, f: F) -> Self
+    where
+        F: Fn(u64) + Send + 'static,
+    {
+        self.body = Box::pin(self.body.inspect(move |chunk| {
+            if let Ok(chunk) = chunk {
+                f(chunk.len() as u64)
+            }
+        }));
+        self
+    }
+
+    /// Read whole body
+    pub async fn bytes(mut self) -> Result<Vec<u8>, Box<dyn Error>> {
+        let mut buf = Vec::with_capacity(capacity_hint(self.content_length));
+        while let Some(chunk) = self.chunk().await? {
+            buf.extend_from_slice(&chunk);
+        }
+        Ok(buf)
+    }
+}
+
+/// Largest buffer preallocated from sizes reported by server or database
+const MAX_CAPACITY_HINT: u64 = 16 * 1024 * 1024;
+
+/// Initial capacity of buffer for body of reported size. Sizes come from server or database,
+/// so they are capped to not abort on allocation of absurd size
+pub(crate) fn capacity_hint(size: Option<u64>) -> usize {
+    size.unwrap_or(0).min(MAX_CAPACITY_HINT) as usize
+}
+
+/// HTTP backend used to download repository databases and packages.
+/// Implement it to use an HTTP stack other than the bundled ones
+///
+/// Bundled implementations are:
+/// - [`ReqwestClient`] (feature `reqwest-client`, enabled by default) - requires tokio runtime
+/// - [`UreqClient`] (feature `ureq-client`) - blocking client driven by a separate thread,
+///   works with any async runtime
+/// - [`MemoryClient`](crate::MemoryClient) - serves files from memory, useful for tests
+pub trait HttpClient: Send + Sync {
+    /// Send GET request to url
+    fn get<'a>(&'a self, url: &'a str) -> BoxFuture<'a, Result<HttpResponse, Box<dyn Error>>>;
+
+    /// Send GET request for `range` bytes of url. Servers without range support answer with
+    /// `200` status and whole body, so callers must check status code.
+    /// Default implementation ignores range and sends plain GET request
+    fn get_range<'a>(
+        &'a self,
+        url: &'a str,
+        range: Range<u64>,
+    ) -> BoxFuture<'a, Result<HttpResponse, Box<dyn Error>>> {
+        let _ = range;
+        self.get(url)
+    }
+
+    /// Send HEAD request to url. Returned response has no body.
+    /// Default implementation sends GET request and doesn't read its body
+    fn head<'a>(&'a self, url: &'a str) -> BoxFuture<'a, Result<HttpResponse, Box<dyn Error>>> {
+        self.get(url)
+    }
+}
+
+/// `Range` header value for byte range
+#[cfg(any(feature = "reqwest-client", feature = "ureq-client"))]
+fn range_header(range: &Range<u64>) -> String {
+    format!("bytes={}-{}", range.start, range.end.saturating_sub(1))
+}
+
+/// IP protocol family used for outgoing connections. Family other than [`IpFamily::Any`] is a
+/// restriction, not a preference: connections are not retried over the other family
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IpFamily {
+    /// Use any address returned by resolver
+    #[default]
+    Any,
+    /// Connect over IPv4 only
+    V4,
+    /// Connect over IPv6 only
+    V6,
+}
+
+/// Connection options applied to bundled HTTP backends
+#[derive(Clone, Debug, Default)]
+pub(crate) struct NetworkOptions {
+    pub(crate) ip_family: IpFamily,
+    pub(crate) local_address: Option<IpAddr>,
+}
+
+impl NetworkOptions {
+    /// Fails if local address doesn't belong to chosen IP family
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        if let Some(address) = self.local_address {
+            let matches = match self.ip_family {
+                IpFamily::Any => true,
+                IpFamily::V4 => address.is_ipv4(),
+                IpFamily::V6 => address.is_ipv6(),
+            };
+            if !matches {
+                return Err(format!(
+                    "Local address {} doesn't belong to IP family {:?}",
+                    address, self.ip_family
+                )
+                .into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Local address outgoing connections are bound to. Unspecified address of chosen family
+    /// makes resolver results of other family unusable
+    #[cfg(feature = "reqwest-client")]
+    fn bind_address(&self) -> Option<IpAddr> {
+        self.local_address.or(match self.ip_family {
+            IpFamily::Any => None,
+            IpFamily::V4 => Some(IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
+            IpFamily::V6 => Some(IpAddr::V6(Ipv6Addr::UNSPECIFIED)),
+        })
+    }
+}
+
+/// Returns client of first enabled backend configured with network options. Fails if network
+/// options conflict or backend can't be configured with them
+pub(crate) fn default_client(
+    network: &NetworkOptions,
+) -> Result<Option<std::sync::Arc<dyn HttpClient>>, Box<dyn Error>> {
+    network.validate()?;
+    #[cfg(feature = "reqwest-client")]
+    return Ok(Some(std::sync::Arc::new(ReqwestClient::with_network(
+        network,
+    )?)));
+    #[cfg(all(not(feature = "reqwest-client"), feature = "ureq-client"))]
+    return Ok(Some(std::sync::Arc::new(UreqClient::with_network(network))));
+    #[cfg(not(any(feature = "reqwest-client", feature = "ureq-client")))]
+    Ok(None)
+}
+
+/// [`HttpClient`] backed by reqwest
+#[cfg(feature = "reqwest-client")]
+#[derive(Clone, Default)]
+pub struct ReqwestClient {
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "reqwest-client")]
+impl ReqwestClient {
+    /// Create backend from configured reqwest client
+    pub fn new(client: reqwest::Client) -> Self {
+        ReqwestClient { client }
+    }
+
+    fn with_network(network: &NetworkOptions) -> Result<Self, Box<dyn Error>> {
+        match network.bind_address() {
+            None => Ok(ReqwestClient::default()),
+            Some(address) => Ok(ReqwestClient::new(
+                reqwest::Client::builder().local_address(address).build()?,
+            )),
+        }
+    }
+}
+
+#[cfg(feature = "reqwest-client")]
+impl ReqwestClient {
+    fn request<'a>(
+        &'a self,
+        url: &'a str,
+        range: Option<Range<u64>>,
+    ) -> BoxFuture<'a, Result<HttpResponse, Box<dyn Error>>> {
+        Box::pin(async move {
+            let mut request = self.client.get(reqwest::Url::parse(url)?);
+            if let Some(range) = range {
+                request = request.header(reqwest::header::RANGE, range_header(&range));
+            }
+            let response = request.send().await?;
+            let status = response.status().as_u16();
+            let content_length = response.content_length();
+            let headers = reqwest_headers(response.headers());
+            let body = futures::stream::try_unfold(response, |mut response| async move {
+                let chunk = response.chunk().await?;
+                Ok::<_, Box<dyn Error + Send + Sync>>(chunk.map(|c| (c.to_vec(), response)))
+            });
+            Ok(HttpResponse::new(status, content_length, Box::pin(body)).with_headers(headers))
+        })
+    }
+}
+
+#[cfg(feature = "reqwest-client")]
+impl HttpClient for ReqwestClient {
+    fn get<'a>(&'a self, url: &'a str) -> BoxFuture<'a, Result<HttpResponse, Box<dyn Error>>> {
+        self.request(url, None)
+    }
+
+    fn get_range<'a>(
+        &'a self,
+        url: &'a str,
+        range: Range<u64>,
+    ) -> BoxFuture<'a, Result<HttpResponse, Box<dyn Error>>> {
+        self.request(url, Some(range))
+    }
+
+    fn head<'a>(&'a self, url: &'a str) -> BoxFuture<'a, Result<HttpResponse, Box<dyn Error>>> {
+        Box::pin(async move {
+            let response = self.client.head(reqwest::Url::parse(url)?).send().await?;
+            // reqwest reports empty body size for HEAD responses
+            let content_length = response
+                .headers()
+                .get(reqwest::header::CONTENT_LENGTH)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse().ok());
+            Ok(HttpResponse::new(
+                response.status().as_u16(),
+                content_length,
+                Box::pin(futures::stream::empty()),
+            )
+            .with_headers(reqwest_headers(response.headers())))
+        })
+    }
+}
+
+#[cfg(feature = "reqwest-client")]
+fn reqwest_headers(headers: &reqwest::header::HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_owned())))
+        .collect()
+}
+
+/// [`HttpClient`] backed by ureq. Every request is driven by its own thread
+#[cfg(feature = "ureq-client")]
+#[derive(Clone)]
+pub struct UreqClient {
+    agent: ureq::Agent,
+}
+
+#[cfg(feature = "ureq-client")]
+impl UreqClient {
+    /// Create backend from configured ureq agent.
+    /// **NOTE! Agent should be configured to not treat HTTP status codes as errors**
+    pub fn new(agent: ureq::Agent) -> Self {
+        UreqClient { agent }
+    }
+}
+
+#[cfg(feature = "ureq-client")]
+impl Default for UreqClient {
+    fn default() -> Self {
+        UreqClient::with_network(&NetworkOptions::default())
+    }
+}
+
+#[cfg(feature = "ureq-client")]
+impl UreqClient {
+    /// ureq can't bind to local address, so only IP family is applied
+    fn with_network(network: &NetworkOptions) -> Self {
+        if let Some(address) = network.local_address {
+            log::warn!(
+                "[archlinux-repo-rs] ureq backend can't bind to local address {}, ignoring it",
+                address
+            );
+        }
+        let ip_family = match network.ip_family {
+            IpFamily::Any => ureq::config::IpFamily::Any,
+            IpFamily::V4 => ureq::config::IpFamily::Ipv4Only,
+            IpFamily::V6 => ureq::config::IpFamily::Ipv6Only,
+        };
+        let config = ureq::Agent::config_builder()
+            .http_status_as_error(false)
+            .ip_family(ip_family)
+            .build();
+        UreqClient::new(config.into())
+    }
+}
+
+#[cfg(feature = "ureq-client")]
+impl UreqClient {
+    fn request<'a>(
+        &'a self,
+        url: &'a str,
+        range: Option<Range<u64>>,
+    ) -> BoxFuture<'a, Result<HttpResponse, Box<dyn Error>>> {
+        use futures::channel::{mpsc, oneshot};
+        use futures::SinkExt;
+        use std::io::Read;
+
+        let agent = self.agent.clone();
+        let url = url.to_owned();
+        Box::pin(async move {
+            let (head_sender, head_receiver) = oneshot::channel();
+            let (mut body_sender, body_receiver) = mpsc::channel(4);
+            std::thread::spawn(move || {
+                let mut request = agent.get(&url);
+                if let Some(range) = range {
+                    request = request.header("Range", range_header(&range));
+                }
+                let response = match request.call() {
+                    Ok(response) => response,
+                    Err(e) => {
+                        let _ = head_sender.send(Err(e));
+                        return;
+                    }
+                };
+                let head = (
+                    response.status().as_u16(),
+                    response.body().content_length(),
+                    ureq_headers(response.headers()),
+                );
+                if head_sender.send(Ok(head)).is_err() {
+                    return;
+                }
+                let mut reader = response.into_body().into_reader();
+                let mut buf = vec![0; 64 * 1024];
+                loop {
+                    let chunk = match reader.read(&mut buf) {
+                        Ok(0) => break,
+                        Ok(read) => Ok(buf[..read].to_vec()),
+                        Err(e) => Err(e),
+                    };
+                    let failed = chunk.is_err();
+                    if futures::executor::block_on(body_sender.send(chunk)).is_err() || failed {
+                        break;
+                    }
+                }
+            });
+            let (status, content_length, headers) = head_receiver.await??;
+            let body = body_receiver.map(|chunk| chunk.map_err(|e| Box::new(e) as _));
+            Ok(HttpResponse::new(status, content_length, Box::pin(body)).with_headers(headers))
+        })
+    }
+}
+
+#[cfg(feature = "ureq-client")]
+impl HttpClient for UreqClient {
+    fn get<'a>(&'a self, url: &'a str) -> BoxFuture<'a, Result<HttpResponse, Box<dyn Error>>> {
+        self.request(url, None)
+    }
+
+    fn get_range<'a>(
+        &'a self,
+        url: &'a str,
+        range: Range<u64>,
+    ) -> BoxFuture<'a, Result<HttpResponse, Box<dyn Error>>> {
+        self.request(url, Some(range))
+    }
+
+    fn head<'a>(&'a self, url: &'a str) -> BoxFuture<'a, Result<HttpResponse, Box<dyn Error>>> {
+        let agent = self.agent.clone();
+        let url = url.to_owned();
+        Box::pin(async move {
+            let (sender, receiver) = futures::channel::oneshot::channel();
+            std::thread::spawn(move || {
+                let head = agent.head(&url).call().map(|response| {
+                    let content_length = response
+                        .headers()
+                        .get("content-length")
+                        .and_then(|v| v.to_str().ok())
+                        .and_then(|v| v.parse().ok());
+                    (
+                        response.status().as_u16(),
+                        content_length,
+                        ureq_headers(response.headers()),
+                    )
+                });
+                let _ = sender.send(head);
+            });
+            let (status, content_length, headers) = receiver.await??;
+            Ok(
+                HttpResponse::new(status, content_length, Box::pin(futures::stream::empty()))
+                    .with_headers(headers),
+            )
+        })
+    }
+}
+
+#[cfg(feature = "ureq-client")]
+fn ureq_headers(headers: &ureq::http::HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_owned())))
+        .collect()
+}
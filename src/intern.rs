@@ -0,0 +1,74 @@
+use crate::footprint::table;
+use crate::{Dependency, Package};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Pool of strings shared between packages. Architecture, packager, licenses, groups and
+/// dependency names repeat across most packages of a repository, so every unique value is
+/// stored once
+#[derive(Default)]
+pub(crate) struct Interner {
+    strings: HashSet<Arc<str>>,
+}
+
+impl Interner {
+    /// Replace repeating package strings with pooled ones
+    pub(crate) fn intern_package(&mut self, package: &mut Package) {
+        self.intern(&mut package.architecture);
+        self.intern(&mut package.packager);
+        self.intern_all(&mut package.license);
+        self.intern_all(&mut package.groups);
+        self.intern_dependencies(&mut package.depends);
+        self.intern_dependencies(&mut package.optdepends);
+        self.intern_dependencies(&mut package.makedepends);
+        self.intern_dependencies(&mut package.checkdepends);
+    }
+
+    /// Approximate memory used by pooled strings
+    pub(crate) fn memory_footprint(&self) -> usize {
+        table::<Arc<str>, ()>(self.strings.capacity())
+            + self
+                .strings
+                .iter()
+                // strong and weak counters are stored with string
+                .map(|s| s.len() + 2 * std::mem::size_of::<usize>())
+                .sum::<usize>()
+    }
+
+    fn intern(&mut self, value: &mut Arc<str>) {
+        match self.strings.get(value) {
+            Some(pooled) => *value = pooled.clone(),
+            None => {
+                self.strings.insert(value.clone());
+            }
+        }
+    }
+
+    fn intern_all(&mut self, values: &mut Option<Vec<Arc<str>>>) {
+        for value in values.iter_mut().flatten() {
+            self.intern(value);
+        }
+    }
+
+    fn intern_dependencies(&mut self, dependencies: &mut Option<Vec<Dependency>>) {
+        for dependency in dependencies.iter_mut().flatten() {
+            self.intern(&mut dependency.name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::intern::Interner;
+    use std::sync::Arc;
+
+    #[test]
+    fn equal_strings_are_shared() {
+        let mut interner = Interner::default();
+        let mut a: Arc<str> = Arc::from("x86_64");
+        let mut b: Arc<str> = Arc::from("x86_64");
+        interner.intern(&mut a);
+        interner.intern(&mut b);
+        assert!(Arc::ptr_eq(&a, &b));
+    }
+}
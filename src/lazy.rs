@@ -0,0 +1,255 @@
+use crate::archive::{self, EntryConsumer, SendError};
+use crate::{metrics, Inner, Package, Progress, RepositoryBuilder, Stage};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::sync::{Arc, OnceLock};
+
+/// Returned when `desc` entry has no `%NAME%`, `%VERSION%` or, for
+/// [`RepositoryBuilder::load_versions`], `%FILENAME%`
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct MissingDescFieldError {
+    entry: String,
+    field: &'static str,
+}
+
+impl Display for MissingDescFieldError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Entry {} has no %{}%", self.entry, self.field)
+    }
+}
+
+impl Error for MissingDescFieldError {}
+
+impl MissingDescFieldError {
+    pub(crate) fn new(path: &str, field: &'static str) -> Self {
+        MissingDescFieldError {
+            entry: path.trim_end_matches("/desc").to_owned(),
+            field,
+        }
+    }
+}
+
+/// Value of `%FIELD%` in `desc` entry without parsing other fields
+pub(crate) fn desc_field<'a>(desc: &'a str, field: &str) -> Option<&'a str> {
+    let mut lines = desc.lines();
+    lines
+        .find(|line| line.strip_prefix('%').and_then(|l| l.strip_suffix('%')) == Some(field))
+        .and(lines.next())
+}
+
+/// Package which `desc` entry is parsed on first access. Name, version and base name are
+/// read without parsing
+pub struct LazyPackage {
+    name: String,
+    version: String,
+    base: Option<String>,
+    path: String,
+    desc: Box<str>,
+    parsed: OnceLock<Package>,
+}
+
+impl LazyPackage {
+    fn new(path: String, desc: String) -> Result<Self, MissingDescFieldError> {
+        let value = |field| desc_field(&desc, field).map(str::to_owned);
+        let missing = |field| MissingDescFieldError::new(&path, field);
+        Ok(LazyPackage {
+            name: value("NAME").ok_or_else(|| missing("NAME"))?,
+            version: value("VERSION").ok_or_else(|| missing("VERSION"))?,
+            base: value("BASE"),
+            desc: desc.into_boxed_str(),
+            path,
+            parsed: OnceLock::new(),
+        })
+    }
+
+    /// Package name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Package version
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Package base name
+    pub fn base(&self) -> Option<&str> {
+        self.base.as_deref()
+    }
+
+    /// Unparsed `desc` entry
+    pub fn raw_desc(&self) -> &str {
+        &self.desc
+    }
+
+    /// Returns `true` if `desc` entry is already parsed
+    pub fn is_parsed(&self) -> bool {
+        self.parsed.get().is_some()
+    }
+
+    /// Parse `desc` entry or return already parsed package. Invalid build date is handled
+    /// according to [`RepositoryBuilder::tolerate_invalid_dates`], warning is logged
+    fn package(&self, options: &LazyOptions) -> Result<&Package, SendError> {
+        if let Some(package) = self.parsed.get() {
+            return Ok(package);
+        }
+        let (mut package, warning) =
+            archive::parse_desc(&self.path, &self.desc, options.tolerate_invalid_dates)?;
+        if let Some(warning) = warning {
+            log::warn!("[archlinux-repo-rs] {}", warning);
+        }
+        if options.keep_raw_desc {
+            package.raw_desc = Some(Arc::from(&*self.desc));
+        }
+        // Concurrent parses may race here, all of them produce same package
+        let _ = self.parsed.set(package);
+        Ok(self.parsed.get().unwrap())
+    }
+}
+
+#[derive(Clone, Copy)]
+struct LazyOptions {
+    tolerate_invalid_dates: bool,
+    keep_raw_desc: bool,
+}
+
+/// Collects `desc` entries without parsing them
+struct LazyDescs {
+    packages: Vec<LazyPackage>,
+}
+
+impl EntryConsumer for LazyDescs {
+    type Output = Vec<LazyPackage>;
+
+    fn consume(&mut self, path: String, contents: String) -> Result<(), SendError> {
+        self.packages.push(LazyPackage::new(path, contents)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Self::Output, SendError> {
+        Ok(self.packages)
+    }
+}
+
+/// Repository which parses package descriptions on first access, see
+/// [`RepositoryBuilder::load_lazy`]. Parsed packages are cached
+pub struct LazyRepository {
+    name: String,
+    url: String,
+    packages: Vec<LazyPackage>,
+    /// Package indexes by names and `name-version` strings
+    index: HashMap<String, usize>,
+    options: LazyOptions,
+}
+
+impl LazyRepository {
+    fn new(name: String, url: String, packages: Vec<LazyPackage>, options: LazyOptions) -> Self {
+        let mut index = HashMap::with_capacity(packages.len() * 2);
+        for (i, package) in packages.iter().enumerate() {
+            index.insert(package.name.clone(), i);
+            index.insert(format!("{}-{}", package.name, package.version), i);
+        }
+        LazyRepository {
+            name,
+            url,
+            packages,
+            index,
+            options,
+        }
+    }
+
+    /// Repository name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Repository url
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Number of packages
+    pub fn len(&self) -> usize {
+        self.packages.len()
+    }
+
+    /// Returns `true` if repository has no packages
+    pub fn is_empty(&self) -> bool {
+        self.packages.is_empty()
+    }
+
+    /// Unparsed packages in database order
+    pub fn packages(&self) -> impl Iterator<Item = &LazyPackage> {
+        self.packages.iter()
+    }
+
+    /// Get unparsed package by full name or name with version
+    pub fn get_lazy(&self, name: &str) -> Option<&LazyPackage> {
+        self.index.get(name).map(|&i| &self.packages[i])
+    }
+
+    /// Get package by full name or name with version, parsing its `desc` entry on first access.
+    /// Returns `Ok(None)` if package cannot be found
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::RepositoryBuilder;
+    ///
+    /// let repo = RepositoryBuilder::new("core", "https://geo.mirror.pkgbuild.com/$repo/os/$arch")
+    ///                 .arch("x86_64")
+    ///                 .load_lazy()
+    ///                 .await?;
+    /// let pacman = repo.get("pacman")?.unwrap();
+    /// ```
+    pub fn get(&self, name: &str) -> Result<Option<&Package>, Box<dyn Error>> {
+        match self.get_lazy(name) {
+            Some(package) => Ok(Some(
+                package
+                    .package(&self.options)
+                    .map_err(|e| e as Box<dyn Error>)?,
+            )),
+            None => Ok(None),
+        }
+    }
+}
+
+impl RepositoryBuilder {
+    /// Download repository database, but parse package descriptions only when they are
+    /// requested. Loading is faster if only a few packages are needed. Packages are
+    /// registered under names and `name-version` strings only, last of duplicated names wins.
+    /// Files metadata, VCS base packages, indexes and validation settings are ignored
+    pub async fn load_lazy(self) -> Result<LazyRepository, Box<dyn Error>> {
+        let url = self.resolved_url()?;
+        let client = self.client()?;
+        let listener = self.progress_listener.as_ref();
+        let events = &self.options;
+        let progress = |p: Progress| {
+            events.event(|e| e.on_progress(&p));
+            if let Some(l) = listener {
+                l(p)
+            }
+        };
+        let db_url = format!("{}/{}", url, self.options.db_file(&self.name));
+        progress(Progress::new(Stage::LoadingDb));
+        self.options.download_started(&db_url);
+        let response = Inner::request(client.as_ref(), &db_url, &self.options).await?;
+        let packages = archive::read_archive(
+            metrics::observe(response, &self.options, &db_url),
+            (Stage::LoadingDb, Stage::ReadingDb),
+            &["/desc"],
+            &self.options,
+            LazyDescs {
+                packages: Vec::new(),
+            },
+            &progress,
+        )
+        .await?;
+        progress(Progress::new(Stage::DbDone));
+        let options = LazyOptions {
+            tolerate_invalid_dates: self.options.tolerate_invalid_dates,
+            keep_raw_desc: self.options.keep_raw_desc,
+        };
+        Ok(LazyRepository::new(self.name, url, packages, options))
+    }
+}
@@ -0,0 +1,113 @@
+use std::fmt::{Display, Formatter};
+use std::io::Read;
+
+/// Size limits for downloaded databases. Protect against decompression bombs when loading
+/// untrusted repositories. All limits are disabled by default
+///
+/// # Example
+/// ```ignore
+/// use archlinux_repo::{Limits, RepositoryBuilder};
+///
+/// RepositoryBuilder::new("mingw64", "http://repo.msys2.org/mingw/x86_64")
+///     .limits(Limits {
+///         compressed_size: Some(64 * 1024 * 1024),
+///         decompressed_size: Some(512 * 1024 * 1024),
+///         entry_size: Some(1024 * 1024),
+///     })
+///     .load()
+///     .await?;
+/// ```
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+pub struct Limits {
+    /// max size of downloaded database in bytes
+    pub compressed_size: Option<u64>,
+    /// max size of decompressed database in bytes
+    pub decompressed_size: Option<u64>,
+    /// max size of single database entry in bytes
+    pub entry_size: Option<u64>,
+}
+
+/// Limit from [`Limits`]
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum Limit {
+    CompressedSize,
+    DecompressedSize,
+    EntrySize,
+}
+
+impl Display for Limit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Limit::CompressedSize => "compressed database size",
+            Limit::DecompressedSize => "decompressed database size",
+            Limit::EntrySize => "database entry size",
+        })
+    }
+}
+
+/// Returned when database exceeds one of [`Limits`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct LimitExceededError {
+    limit: Limit,
+    max: u64,
+}
+
+impl LimitExceededError {
+    pub(crate) fn new(limit: Limit, max: u64) -> Self {
+        LimitExceededError { limit, max }
+    }
+
+    /// Exceeded limit
+    pub fn limit(&self) -> Limit {
+        self.limit
+    }
+
+    /// Limit value in bytes
+    pub fn max(&self) -> u64 {
+        self.max
+    }
+}
+
+impl Display for LimitExceededError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            formatter,
+            "Max {} of {} bytes exceeded",
+            self.limit, self.max
+        )
+    }
+}
+
+impl std::error::Error for LimitExceededError {}
+
+/// Reader which fails with [`LimitExceededError`] after `max` bytes
+pub(crate) struct LimitedReader<R> {
+    inner: R,
+    limit: Limit,
+    max: Option<u64>,
+    read: u64,
+}
+
+impl<R: Read> LimitedReader<R> {
+    pub(crate) fn new(inner: R, limit: Limit, max: Option<u64>) -> Self {
+        LimitedReader {
+            inner,
+            limit,
+            max,
+            read: 0,
+        }
+    }
+}
+
+impl<R: Read> Read for LimitedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.read += read as u64;
+        match self.max {
+            Some(max) if self.read > max => Err(std::io::Error::other(LimitExceededError::new(
+                self.limit, max,
+            ))),
+            _ => Ok(read),
+        }
+    }
+}
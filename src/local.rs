@@ -0,0 +1,96 @@
+use crate::Dependency;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::path::Path;
+
+/// Package installed in local system
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug)]
+pub struct InstalledPackage {
+    /// name
+    #[serde(rename = "NAME")]
+    pub name: String,
+    /// installed version
+    #[serde(rename = "VERSION")]
+    pub version: String,
+    /// name without architecture
+    #[serde(rename = "BASE")]
+    pub base: Option<String>,
+    /// description
+    #[serde(rename = "DESC")]
+    pub description: Option<String>,
+    /// processor architecture
+    #[serde(rename = "ARCH")]
+    pub architecture: Option<String>,
+    /// `1` if package was installed as a dependency
+    #[serde(rename = "REASON")]
+    pub reason: Option<u8>,
+    /// package groups
+    #[serde(rename = "GROUPS")]
+    pub groups: Option<Vec<String>>,
+    /// packages which this package replaces
+    #[serde(rename = "REPLACES")]
+    pub replaces: Option<Vec<String>>,
+    /// packages provided by this package
+    #[serde(rename = "PROVIDES")]
+    pub provides: Option<Vec<String>>,
+    /// run-time dependencies
+    #[serde(rename = "DEPENDS")]
+    pub depends: Option<Vec<Dependency>>,
+}
+
+impl InstalledPackage {
+    /// Returns `true` if package was installed explicitly, not as a dependency
+    pub fn is_explicit(&self) -> bool {
+        self.reason.unwrap_or(0) == 0
+    }
+}
+
+/// Database of installed packages, which pacman keeps in `local` directory of its database
+/// path
+///
+/// # Example
+/// ```ignore
+/// use archlinux_repo::LocalDatabase;
+///
+/// let local = LocalDatabase::open(LocalDatabase::DEFAULT_PATH)?;
+/// for package in local.packages() {
+///     println!("{} {}", &package.name, &package.version);
+/// }
+/// ```
+pub struct LocalDatabase {
+    packages: Vec<InstalledPackage>,
+}
+
+impl LocalDatabase {
+    /// Default pacman database path
+    pub const DEFAULT_PATH: &'static str = "/var/lib/pacman";
+
+    /// Read installed packages from pacman database path, like [`LocalDatabase::DEFAULT_PATH`]
+    pub fn open<P: AsRef<Path>>(db_path: P) -> Result<Self, Box<dyn Error>> {
+        let mut packages = Vec::new();
+        for entry in std::fs::read_dir(db_path.as_ref().join("local"))? {
+            let desc = entry?.path().join("desc");
+            if desc.is_file() {
+                let contents = std::fs::read_to_string(&desc)?;
+                packages.push(archlinux_repo_parser::from_str::<InstalledPackage>(
+                    &contents,
+                )?);
+            }
+        }
+        packages.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(LocalDatabase { packages })
+    }
+
+    /// Installed packages sorted by name
+    pub fn packages(&self) -> &[InstalledPackage] {
+        &self.packages
+    }
+
+    /// Get installed package by name
+    pub fn get(&self, name: &str) -> Option<&InstalledPackage> {
+        self.packages
+            .binary_search_by(|p| p.name.as_str().cmp(name))
+            .ok()
+            .map(|i| &self.packages[i])
+    }
+}
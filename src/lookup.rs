@@ -0,0 +1,100 @@
+use crate::{Package, Repository};
+use std::fmt::{Display, Formatter};
+
+/// Returned by [`Repository::lookup`] when key doesn't identify exactly one package
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum LookupError {
+    /// No package has this name, base name or name with version
+    NotFound { key: String },
+    /// Key is base name of several split packages and none of them has this name
+    Ambiguous {
+        key: String,
+        /// names of split packages in repository order
+        candidates: Vec<String>,
+    },
+    /// Key is name with version, but repository has package with this name only in other
+    /// version
+    OtherVersion {
+        key: String,
+        /// name with version of available package
+        available: String,
+    },
+}
+
+impl Display for LookupError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LookupError::NotFound { key } => write!(f, "Package {} not found", key),
+            LookupError::Ambiguous { key, candidates } => write!(
+                f,
+                "Package base {} has several packages: {}",
+                key,
+                candidates.join(", ")
+            ),
+            LookupError::OtherVersion { key, available } => {
+                write!(f, "Package {} not found, repository has {}", key, available)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LookupError {}
+
+impl Repository {
+    /// Find package by full name, base name or name with version without panicking, unlike
+    /// `repo[name]`. Base name of several split packages is accepted only if one of them has
+    /// the same name
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::{LookupError, Repository};
+    ///
+    /// let repo = Repository::load("mingw64", "http://repo.msys2.org/mingw/x86_64").await?;
+    /// match repo.lookup("mingw-w64-x86_64-gtk3-3.0-1") {
+    ///     Ok(package) => println!("{}", package.version),
+    ///     Err(LookupError::OtherVersion { available, .. }) => println!("try {}", available),
+    ///     Err(e) => println!("{}", e),
+    /// }
+    /// ```
+    pub fn lookup(&self, key: &str) -> Result<&Package, LookupError> {
+        let entry = match self.inner.index.get(key) {
+            Some(entry) => entry,
+            None => return Err(self.missing(key)),
+        };
+        match entry.base.as_slice() {
+            [package] => return Ok(package),
+            [] => {}
+            split => {
+                if entry.name.is_none() {
+                    return Err(LookupError::Ambiguous {
+                        key: key.to_owned(),
+                        candidates: split.iter().map(|p| p.name.clone()).collect(),
+                    });
+                }
+            }
+        }
+        entry
+            .name
+            .as_deref()
+            .or(entry.name_version.as_deref())
+            .ok_or_else(|| self.missing(key))
+    }
+
+    /// Error for key which is not indexed. Versions have `pkgver-pkgrel` form, so name is
+    /// everything before second to last dash
+    fn missing(&self, key: &str) -> LookupError {
+        let available = key
+            .rsplitn(3, '-')
+            .nth(2)
+            .and_then(|name| self.inner.get_by_name(name));
+        match available {
+            Some(package) => LookupError::OtherVersion {
+                key: key.to_owned(),
+                available: format!("{}-{}", package.name, package.version),
+            },
+            None => LookupError::NotFound {
+                key: key.to_owned(),
+            },
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use crate::{Package, RepositorySnapshot};
+use chrono::{DateTime, Utc};
+use memmap2::Mmap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+/// First bytes of mapped snapshot file
+const MAGIC: &[u8; 8] = b"ALRMAP01";
+
+/// Returned when file is not a mapped snapshot or is truncated
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct InvalidMappedSnapshotError {
+    reason: &'static str,
+}
+
+impl Display for InvalidMappedSnapshotError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid mapped snapshot: {}", self.reason)
+    }
+}
+
+impl Error for InvalidMappedSnapshotError {}
+
+fn invalid(reason: &'static str) -> InvalidMappedSnapshotError {
+    InvalidMappedSnapshotError { reason }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Header {
+    name: String,
+    url: String,
+    #[serde(with = "crate::data::date_serde")]
+    created: DateTime<Utc>,
+    entries: Vec<Entry>,
+}
+
+/// Location of package `desc` blob, offset is relative to end of header
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    name: String,
+    version: String,
+    offset: u64,
+    length: u64,
+}
+
+impl RepositorySnapshot {
+    /// Write snapshot in format which can be opened with [`MappedSnapshot::open`]. File
+    /// consists of magic bytes, header length, JSON header with package index and `desc`
+    /// blobs of packages. Files metadata is not written
+    pub fn write_mapped<W: Write>(&self, mut writer: W) -> Result<(), Box<dyn Error>> {
+        let mut blobs = Vec::new();
+        let mut entries = Vec::with_capacity(self.packages.len());
+        for package in &self.packages {
+            let desc = package.to_desc_string()?;
+            entries.push(Entry {
+                name: package.name.clone(),
+                version: package.version.clone(),
+                offset: blobs.len() as u64,
+                length: desc.len() as u64,
+            });
+            blobs.extend_from_slice(desc.as_bytes());
+        }
+        let header = serde_json::to_vec(&Header {
+            name: self.name.clone(),
+            url: self.url.clone(),
+            created: self.created,
+            entries,
+        })?;
+        writer.write_all(MAGIC)?;
+        writer.write_all(&(header.len() as u64).to_le_bytes())?;
+        writer.write_all(&header)?;
+        writer.write_all(&blobs)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Repository snapshot backed by memory-mapped file. Only package index is kept in memory,
+/// packages are parsed from mapped `desc` blobs on every access, so many large snapshots can
+/// be open at once. Files are written by [`RepositorySnapshot::write_mapped`]
+///
+/// # Example
+/// ```ignore
+/// use archlinux_repo::{MappedSnapshot, Repository};
+///
+/// let repo = Repository::load("mingw64", "http://repo.msys2.org/mingw/x86_64").await?;
+/// repo.snapshot().write_mapped(std::fs::File::create("mingw64.map")?)?;
+/// let snapshot = MappedSnapshot::open("mingw64.map")?;
+/// let gtk = snapshot.get("mingw-w64-x86_64-gtk3")?.unwrap();
+/// ```
+pub struct MappedSnapshot {
+    map: Mmap,
+    /// Offset of first blob in file
+    blobs: usize,
+    header: Header,
+    /// Entry indexes by names and `name-version` strings
+    index: HashMap<String, usize>,
+}
+
+impl MappedSnapshot {
+    /// Map snapshot file. File must not be modified while it is mapped
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn Error>> {
+        let file = File::open(path)?;
+        // Safety: snapshot files are written once and are not modified while mapped
+        let map = unsafe { Mmap::map(&file)? };
+        if map.len() < 16 || &map[..8] != MAGIC {
+            return Err(Box::new(invalid("unknown file format")));
+        }
+        let mut length = [0; 8];
+        length.copy_from_slice(&map[8..16]);
+        let blobs = usize::try_from(u64::from_le_bytes(length))
+            .ok()
+            .and_then(|length| length.checked_add(16))
+            .filter(|&end| end <= map.len())
+            .ok_or_else(|| invalid("header is truncated"))?;
+        let header: Header = serde_json::from_slice(&map[16..blobs])?;
+        let size = (map.len() - blobs) as u64;
+        if header.entries.iter().any(|e| {
+            e.offset
+                .checked_add(e.length)
+                .filter(|&end| end <= size)
+                .is_none()
+        }) {
+            return Err(Box::new(invalid("package is truncated")));
+        }
+        let mut index = HashMap::with_capacity(header.entries.len() * 2);
+        for (i, entry) in header.entries.iter().enumerate() {
+            index.insert(entry.name.clone(), i);
+            index.insert(format!("{}-{}", entry.name, entry.version), i);
+        }
+        Ok(MappedSnapshot {
+            map,
+            blobs,
+            header,
+            index,
+        })
+    }
+
+    /// Repository name
+    pub fn name(&self) -> &str {
+        &self.header.name
+    }
+
+    /// Repository url
+    pub fn url(&self) -> &str {
+        &self.header.url
+    }
+
+    /// When repository was loaded
+    pub fn created(&self) -> DateTime<Utc> {
+        self.header.created
+    }
+
+    /// Number of packages
+    pub fn len(&self) -> usize {
+        self.header.entries.len()
+    }
+
+    /// Returns `true` if snapshot has no packages
+    pub fn is_empty(&self) -> bool {
+        self.header.entries.is_empty()
+    }
+
+    /// Package names and versions in snapshot order
+    pub fn names(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.header
+            .entries
+            .iter()
+            .map(|e| (e.name.as_str(), e.version.as_str()))
+    }
+
+    /// Parse package by full name or name with version. Returns `Ok(None)` if package cannot
+    /// be found
+    pub fn get(&self, name: &str) -> Result<Option<Package>, Box<dyn Error>> {
+        match self.index.get(name) {
+            Some(&i) => Ok(Some(self.parse(&self.header.entries[i])?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Parse all packages into in-memory snapshot
+    pub fn to_snapshot(&self) -> Result<RepositorySnapshot, Box<dyn Error>> {
+        Ok(RepositorySnapshot {
+            name: self.header.name.clone(),
+            url: self.header.url.clone(),
+            created: self.header.created,
+            packages: self
+                .header
+                .entries
+                .iter()
+                .map(|e| self.parse(e))
+                .collect::<Result<_, _>>()?,
+            files: None,
+            last_modified: None,
+            etag: None,
+        })
+    }
+
+    fn parse(&self, entry: &Entry) -> Result<Package, Box<dyn Error>> {
+        let start = self.blobs + entry.offset as usize;
+        let desc = std::str::from_utf8(&self.map[start..start + entry.length as usize])?;
+        Ok(archlinux_repo_parser::from_str(desc)?)
+    }
+}
@@ -0,0 +1,169 @@
+use crate::http::{HttpClient, HttpResponse};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use futures::future::BoxFuture;
+use std::collections::HashMap;
+use std::error::Error;
+use std::ops::Range;
+
+/// Default size of body chunks returned by [`MemoryClient`]
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// [`HttpClient`] which serves files from memory by exact url. Missing files are answered with
+/// `404` status. Use it to test code against synthetic repositories without network access
+///
+/// # Example
+/// ```ignore
+/// use archlinux_repo::{MemoryClient, RepositoryBuilder};
+///
+/// let mut client = MemoryClient::new();
+/// client.insert_archive(
+///     "memory://repo/test.db.tar.gz",
+///     &[("foo-1.0-1/desc", "%NAME%\nfoo\n\n%VERSION%\n1.0-1\n\n...")],
+/// )?;
+/// let repo = RepositoryBuilder::new("test", "memory://repo")
+///                 .http_client(client)
+///                 .load()
+///                 .await?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct MemoryClient {
+    files: HashMap<String, Vec<u8>>,
+    chunk_size: usize,
+}
+
+impl Default for MemoryClient {
+    fn default() -> Self {
+        MemoryClient {
+            files: HashMap::new(),
+            chunk_size: CHUNK_SIZE,
+        }
+    }
+}
+
+impl MemoryClient {
+    /// Create client without files
+    pub fn new() -> Self {
+        MemoryClient::default()
+    }
+
+    /// Set size of response body chunks. Small chunks help to test code which reads body
+    /// across chunk boundaries. Defaults to 64 KiB
+    pub fn chunk_size(mut self, size: usize) -> Self {
+        self.chunk_size = size.max(1);
+        self
+    }
+
+    /// Serve file contents at url
+    pub fn file<C: Into<Vec<u8>>>(mut self, url: &str, contents: C) -> Self {
+        self.insert(url, contents);
+        self
+    }
+
+    /// Serve file contents at url, replacing previous contents
+    pub fn insert<C: Into<Vec<u8>>>(&mut self, url: &str, contents: C) {
+        self.files.insert(url.to_owned(), contents.into());
+    }
+
+    /// Serve gzipped tar archive built from `(path, contents)` entries at url, like
+    /// `foo-1.0-1/desc` entries of repository database
+    pub fn insert_archive(
+        &mut self,
+        url: &str,
+        entries: &[(&str, &str)],
+    ) -> Result<(), std::io::Error> {
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        for (path, contents) in entries {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, path, contents.as_bytes())?;
+        }
+        let archive = builder.into_inner()?.finish()?;
+        self.insert(url, archive);
+        Ok(())
+    }
+
+    fn serve(&self, url: &str, range: Range<u64>, status: u16, body: bool) -> HttpResponse {
+        let file = match self.files.get(url) {
+            Some(file) => file,
+            None => return HttpResponse::new(404, None, Box::pin(futures::stream::empty())),
+        };
+        if range.start > file.len() as u64 {
+            return HttpResponse::new(416, None, Box::pin(futures::stream::empty()));
+        }
+        let start = range.start as usize;
+        let end = (file.len() as u64).min(range.end) as usize;
+        let chunks: Vec<Result<Vec<u8>, Box<dyn Error + Send + Sync>>> = if body {
+            file[start..end]
+                .chunks(self.chunk_size)
+                .map(|c| Ok(c.to_vec()))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        HttpResponse::new(
+            status,
+            Some((end - start) as u64),
+            Box::pin(futures::stream::iter(chunks)),
+        )
+    }
+}
+
+impl HttpClient for MemoryClient {
+    fn get<'a>(&'a self, url: &'a str) -> BoxFuture<'a, Result<HttpResponse, Box<dyn Error>>> {
+        Box::pin(async move { Ok(self.serve(url, 0..u64::MAX, 200, true)) })
+    }
+
+    fn get_range<'a>(
+        &'a self,
+        url: &'a str,
+        range: Range<u64>,
+    ) -> BoxFuture<'a, Result<HttpResponse, Box<dyn Error>>> {
+        Box::pin(async move { Ok(self.serve(url, range, 206, true)) })
+    }
+
+    fn head<'a>(&'a self, url: &'a str) -> BoxFuture<'a, Result<HttpResponse, Box<dyn Error>>> {
+        Box::pin(async move { Ok(self.serve(url, 0..u64::MAX, 200, false)) })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{HttpClient, MemoryClient, RepositoryBuilder};
+
+    #[tokio::test]
+    async fn memory_client_serves_synthetic_repo() {
+        let mut client = MemoryClient::new().file("memory://repo/foo-1.0-1-any.pkg.tar.zst", "foo");
+        client
+            .insert_archive(
+                "memory://repo/test.db.tar.gz",
+                &[(
+                    "foo-1.0-1/desc",
+                    "%FILENAME%\nfoo-1.0-1-any.pkg.tar.zst\n\n%NAME%\nfoo\n\n%VERSION%\n1.0-1\n\n\
+                    %CSIZE%\n3\n\n%ISIZE%\n3\n\n%MD5SUM%\nacbd18db4cc2f85cedef654fccc4a4d8\n\n\
+                    %SHA256SUM%\n2c26b46b68ffc68ff99b453c1d30413413422d706483bfa0f98a5e886266e7ae\n\n\
+                    %PGPSIG%\nc2lnbmF0dXJl\n\n%ARCH%\nany\n\n%BUILDDATE%\n1560520506\n\n\
+                    %PACKAGER%\nTest <test@example.com>\n\n",
+                )],
+            )
+            .unwrap();
+        assert_eq!(
+            404,
+            client.get("memory://repo/missing").await.unwrap().status()
+        );
+        let head = client.head("memory://repo/test.db.tar.gz").await.unwrap();
+        assert!(head.content_length().unwrap() > 0);
+        assert!(head.bytes().await.unwrap().is_empty());
+
+        let repo = RepositoryBuilder::new("test", "memory://repo")
+            .http_client(client)
+            .load()
+            .await
+            .unwrap();
+        assert_eq!("1.0-1", repo["foo"].version);
+        let package = repo.request_package("foo").await.unwrap();
+        assert_eq!(b"foo".to_vec(), package.bytes().await.unwrap());
+    }
+}
@@ -0,0 +1,68 @@
+use crate::{HttpResponse, LoadOptions, Stage};
+use std::time::Duration;
+
+/// Receives measurements of repository loading and package downloads. All methods do nothing
+/// by default, so implementations override only measurements they export. Methods are called
+/// from loading threads and must not block
+///
+/// # Example
+/// ```ignore
+/// use archlinux_repo::{Metrics, RepositoryBuilder};
+/// use std::sync::atomic::{AtomicU64, Ordering};
+///
+/// #[derive(Default)]
+/// struct Downloaded(AtomicU64);
+///
+/// impl Metrics for Downloaded {
+///     fn bytes_downloaded(&self, _: &str, bytes: u64) {
+///         self.0.fetch_add(bytes, Ordering::Relaxed);
+///     }
+/// }
+///
+/// let repo = RepositoryBuilder::new("mingw64", "http://repo.msys2.org/mingw/x86_64")
+///                 .metrics(Downloaded::default())
+///                 .load()
+///                 .await?;
+/// ```
+pub trait Metrics: Send + Sync {
+    /// Called for every downloaded response body chunk of database or package
+    fn bytes_downloaded(&self, url: &str, bytes: u64) {
+        let _ = (url, bytes);
+    }
+
+    /// Called when archive is read. `duration` is time spent on decompression and tar
+    /// reading, without entry parsing. `stage` is [`Stage::ReadingDb`] or
+    /// [`Stage::ReadingFilesMetadata`]
+    fn archive_decompressed(&self, stage: Stage, duration: Duration) {
+        let _ = (stage, duration);
+    }
+
+    /// Called when archive entries are parsed. `stage` is [`Stage::ReadingDb`] or
+    /// [`Stage::ReadingFilesMetadata`]
+    fn entries_parsed(&self, stage: Stage, entries: u64, duration: Duration) {
+        let _ = (stage, entries, duration);
+    }
+
+    /// Called when repository database is loaded with amount of indexed packages, including
+    /// base packages created for VCS packages
+    fn packages_loaded(&self, repository: &str, packages: u64) {
+        let _ = (repository, packages);
+    }
+
+    /// Called when request to url is repeated
+    fn request_retried(&self, url: &str) {
+        let _ = url;
+    }
+}
+
+/// Report body chunks of response to metrics set in `options`
+pub(crate) fn observe(response: HttpResponse, options: &LoadOptions, url: &str) -> HttpResponse {
+    match &options.metrics {
+        Some(metrics) => {
+            let metrics = metrics.clone();
+            let url = options.public_url(url).into_owned();
+            response.on_chunk(move |bytes| metrics.bytes_downloaded(&url, bytes))
+        }
+        None => response,
+    }
+}
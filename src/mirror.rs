@@ -0,0 +1,182 @@
+use crate::{
+    cache, metrics, repo_url, Inner, InvalidUrlError, MissingSignatureError, Package, Repository,
+    RepositoryBuilder, SignatureCheck,
+};
+use sha2::{Digest, Sha256};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Package file downloaded by [`Repository::fetch_package`]
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct FetchedPackage {
+    /// url of mirror which served package, `None` if package was taken from package cache
+    pub mirror: Option<String>,
+    /// package file contents
+    pub data: Vec<u8>,
+    /// detached signature from the same mirror, `None` if mirror doesn't have it or package
+    /// was taken from package cache
+    pub signature: Option<Vec<u8>>,
+}
+
+/// Returned when package cannot be downloaded from any mirror
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct MirrorsFailedError {
+    failures: Vec<(String, String)>,
+}
+
+impl MirrorsFailedError {
+    /// Mirror urls with descriptions of their errors, in order of attempts
+    pub fn failures(&self) -> &[(String, String)] {
+        &self.failures
+    }
+}
+
+impl Display for MirrorsFailedError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Package download failed on all mirrors")?;
+        for (mirror, error) in &self.failures {
+            write!(f, "; {}: {}", mirror, error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for MirrorsFailedError {}
+
+impl RepositoryBuilder {
+    /// Add mirror which is used by [`Repository::fetch_package`] when previous mirrors fail.
+    /// Repository url is always tried first. Mirror url can be server template, like
+    /// repository url
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::RepositoryBuilder;
+    ///
+    /// let repo = RepositoryBuilder::new("core", "https://geo.mirror.pkgbuild.com/$repo/os/$arch")
+    ///                 .mirror("https://mirror.rackspace.com/archlinux/$repo/os/$arch")
+    ///                 .arch("x86_64")
+    ///                 .load()
+    ///                 .await?;
+    /// ```
+    pub fn mirror(mut self, url: &str) -> Self {
+        self.mirrors.push(url.to_owned());
+        self
+    }
+
+    /// Mirror urls with substituted server template variables
+    pub(crate) fn resolved_mirrors(&self) -> Result<Vec<String>, InvalidUrlError> {
+        self.mirrors
+            .iter()
+            .map(|url| repo_url::expand_url(url, Some(&self.name), self.arch.as_deref()))
+            .collect()
+    }
+}
+
+impl Repository {
+    /// Repository url followed by mirrors set with [`RepositoryBuilder::mirror`]
+    pub fn mirrors(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.url.as_str()).chain(self.mirrors.iter().map(|m| m.as_str()))
+    }
+
+    /// Download whole package file with its detached signature by full name/base name or name
+    /// with version and verify its SHA256 checksum. If download fails or checksum doesn't
+    /// match, for example on stale mirror, next mirror is tried. Package cache directories are
+    /// checked first. Fails with [`LookupError`](crate::LookupError) if package cannot be found
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::RepositoryBuilder;
+    ///
+    /// let repo = RepositoryBuilder::new("mingw64", "http://repo.msys2.org/mingw/x86_64")
+    ///                 .mirror("https://mirror.msys2.org/mingw/x86_64")
+    ///                 .load()
+    ///                 .await?;
+    /// let package = repo.fetch_package("mingw-w64-x86_64-gtk3").await?;
+    /// println!("downloaded from {:?}", package.mirror);
+    /// ```
+    pub async fn fetch_package(&self, name: &str) -> Result<FetchedPackage, Box<dyn Error>> {
+        let package = self.lookup(name)?;
+        // cached packages have no detached signatures
+        let cached = if self.lacks_required_signature(package) {
+            None
+        } else {
+            cache::cached_package(&self.package_cache, &package.file_name, &package.sha256_sum)
+                .await
+        };
+        if let Some(response) = cached {
+            return Ok(FetchedPackage {
+                mirror: None,
+                data: response.bytes().await?,
+                signature: None,
+            });
+        }
+        let mut failures = Vec::new();
+        for mirror in self.mirrors() {
+            let url = format!("{}/{}", self.package_dir(mirror), package.file_name);
+            if !failures.is_empty() {
+                self.options.retried(&url);
+            }
+            match self.fetch_verified(&url, package).await {
+                Ok(data) => {
+                    let signature = match self.package_signature_check() {
+                        SignatureCheck::Never => None,
+                        _ => self.fetch_signature(&url).await,
+                    };
+                    if signature.is_none() && self.lacks_required_signature(package) {
+                        let error = MissingSignatureError::new(&self.options.public_url(&url));
+                        log::debug!("[archlinux-repo-rs] {}", error);
+                        failures.push((
+                            self.options.public_url(mirror).into_owned(),
+                            error.to_string(),
+                        ));
+                        continue;
+                    }
+                    return Ok(FetchedPackage {
+                        mirror: Some(mirror.to_owned()),
+                        data,
+                        signature,
+                    });
+                }
+                Err(e) => {
+                    log::debug!(
+                        "[archlinux-repo-rs] Failed to download {}: {}",
+                        self.options.public_url(&url),
+                        e
+                    );
+                    failures.push((self.options.public_url(mirror).into_owned(), e.to_string()));
+                }
+            }
+        }
+        Err(Box::new(MirrorsFailedError { failures }))
+    }
+
+    async fn fetch_signature(&self, url: &str) -> Option<Vec<u8>> {
+        let url = format!("{}.sig", url);
+        self.options.download_started(&url);
+        let response = Inner::request(self.http_client.as_ref(), &url, &self.options)
+            .await
+            .ok()?;
+        metrics::observe(response, &self.options, &url)
+            .bytes()
+            .await
+            .ok()
+    }
+
+    async fn fetch_verified(
+        &self,
+        url: &str,
+        package: &Package,
+    ) -> Result<Vec<u8>, Box<dyn Error>> {
+        self.options.download_started(url);
+        let response = Inner::request(self.http_client.as_ref(), url, &self.options).await?;
+        let data = metrics::observe(response, &self.options, url)
+            .bytes()
+            .await?;
+        if !package.sha256_sum.is_empty()
+            && format!("{:x}", Sha256::digest(&data)) != package.sha256_sum.to_lowercase()
+        {
+            return Err("checksum mismatch".into());
+        }
+        Ok(data)
+    }
+}
@@ -0,0 +1,260 @@
+use crate::{HttpClient, HttpError, RepositoryBuilder};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::error::Error;
+
+/// Arch Linux mirror status url
+pub const MIRROR_STATUS_URL: &str = "https://archlinux.org/mirrors/status/json/";
+
+/// Mirror from Arch Linux mirror status
+#[derive(Clone, PartialEq, Debug)]
+pub struct MirrorStatus {
+    /// mirror root url with trailing slash, like `https://mirror.example.com/archlinux/`
+    pub url: String,
+    /// `http`, `https` or `rsync`
+    pub protocol: String,
+    /// `None` if mirror was never synced successfully
+    pub last_sync: Option<DateTime<Utc>>,
+    /// share of successful checks, from `0.0` to `1.0`
+    pub completion: f64,
+    /// mean delay behind main mirror in seconds
+    pub delay: Option<u64>,
+    /// mirror score computed by archlinux.org, lower is better
+    pub score: Option<f64>,
+    pub country: String,
+    /// ISO 3166 country code, empty for worldwide mirrors
+    pub country_code: String,
+    pub ipv4: bool,
+    pub ipv6: bool,
+}
+
+impl MirrorStatus {
+    /// Pacman-style server template of mirror, like
+    /// `https://mirror.example.com/archlinux/$repo/os/$arch`
+    pub fn server(&self) -> String {
+        format!("{}/$repo/os/$arch", self.url.trim_end_matches('/'))
+    }
+}
+
+#[derive(Deserialize)]
+struct StatusResponse {
+    urls: Vec<RawMirror>,
+}
+
+#[derive(Deserialize)]
+struct RawMirror {
+    url: String,
+    protocol: String,
+    last_sync: Option<String>,
+    completion_pct: Option<f64>,
+    delay: Option<u64>,
+    score: Option<f64>,
+    active: bool,
+    #[serde(default)]
+    country: String,
+    #[serde(default)]
+    country_code: String,
+    #[serde(default)]
+    ipv4: bool,
+    #[serde(default)]
+    ipv6: bool,
+}
+
+impl RawMirror {
+    fn into_status(self) -> MirrorStatus {
+        MirrorStatus {
+            last_sync: self
+                .last_sync
+                .as_deref()
+                .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+                .map(|d| d.with_timezone(&Utc)),
+            url: self.url,
+            protocol: self.protocol,
+            completion: self.completion_pct.unwrap_or(0.0),
+            delay: self.delay,
+            score: self.score,
+            country: self.country,
+            country_code: self.country_code,
+            ipv4: self.ipv4,
+            ipv6: self.ipv6,
+        }
+    }
+}
+
+/// Query of Arch Linux mirror status. Only active, fully synced and scored mirrors are
+/// returned, best ones first
+///
+/// # Example
+/// ```ignore
+/// use archlinux_repo::{MirrorStatusQuery, ReqwestClient};
+///
+/// let mirrors = MirrorStatusQuery::new()
+///                 .country("DE")
+///                 .protocol("https")
+///                 .limit(5)
+///                 .fetch(&ReqwestClient::default())
+///                 .await?;
+/// for mirror in mirrors {
+///     println!("{} {:?}", mirror.server(), mirror.score);
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct MirrorStatusQuery {
+    url: String,
+    countries: Vec<String>,
+    protocols: Vec<String>,
+    limit: Option<usize>,
+}
+
+impl Default for MirrorStatusQuery {
+    fn default() -> Self {
+        MirrorStatusQuery::new()
+    }
+}
+
+impl MirrorStatusQuery {
+    /// Create query of all mirrors from [`MIRROR_STATUS_URL`]
+    pub fn new() -> Self {
+        MirrorStatusQuery {
+            url: MIRROR_STATUS_URL.to_owned(),
+            countries: Vec::new(),
+            protocols: Vec::new(),
+            limit: None,
+        }
+    }
+
+    /// Set mirror status url
+    pub fn url(mut self, url: &str) -> Self {
+        self.url = url.to_owned();
+        self
+    }
+
+    /// Allow mirrors from country with ISO 3166 code, like `DE`. All countries are allowed
+    /// by default
+    pub fn country(mut self, code: &str) -> Self {
+        self.countries.push(code.to_uppercase());
+        self
+    }
+
+    /// Allow mirrors with protocol, like `https`. All protocols are allowed by default
+    pub fn protocol(mut self, protocol: &str) -> Self {
+        self.protocols.push(protocol.to_lowercase());
+        self
+    }
+
+    /// Return at most `limit` best mirrors
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Download mirror status and select mirrors
+    pub async fn fetch(
+        &self,
+        client: &dyn HttpClient,
+    ) -> Result<Vec<MirrorStatus>, Box<dyn Error>> {
+        let response = client.get(&self.url).await?;
+        if !response.is_success() {
+            return Err(Box::new(
+                HttpError::from_response("GET", &self.url, response).await,
+            ));
+        }
+        let status: StatusResponse = serde_json::from_slice(&response.bytes().await?)?;
+        Ok(self.select(status.urls))
+    }
+
+    fn select(&self, mirrors: Vec<RawMirror>) -> Vec<MirrorStatus> {
+        let mut mirrors: Vec<MirrorStatus> = mirrors
+            .into_iter()
+            .filter(|m| m.active)
+            .map(RawMirror::into_status)
+            .filter(|m| m.score.is_some() && m.completion >= 1.0)
+            .filter(|m| self.countries.is_empty() || self.countries.contains(&m.country_code))
+            .filter(|m| self.protocols.is_empty() || self.protocols.contains(&m.protocol))
+            .collect();
+        mirrors.sort_by(|a, b| a.score.partial_cmp(&b.score).unwrap());
+        if let Some(limit) = self.limit {
+            mirrors.truncate(limit);
+        }
+        mirrors
+    }
+}
+
+impl RepositoryBuilder {
+    /// Add mirrors selected by query as failover mirrors, see [`RepositoryBuilder::mirror`].
+    /// Mirror status is downloaded with configured HTTP client. Rsync mirrors are skipped
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::{MirrorStatusQuery, RepositoryBuilder};
+    ///
+    /// let repo = RepositoryBuilder::new("core", "https://geo.mirror.pkgbuild.com/$repo/os/$arch")
+    ///                 .arch("x86_64")
+    ///                 .mirrors_from_status(&MirrorStatusQuery::new().country("DE").limit(3))
+    ///                 .await?
+    ///                 .load()
+    ///                 .await?;
+    /// ```
+    pub async fn mirrors_from_status(
+        mut self,
+        query: &MirrorStatusQuery,
+    ) -> Result<Self, Box<dyn Error>> {
+        let client = self.client()?;
+        let mirrors = query.fetch(client.as_ref()).await?;
+        self.mirrors.extend(
+            mirrors
+                .iter()
+                .filter(|m| m.protocol != "rsync")
+                .map(MirrorStatus::server),
+        );
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::mirror_status::StatusResponse;
+    use crate::MirrorStatusQuery;
+
+    const STATUS: &str = r#"{"cutoff": 86400, "urls": [
+        {"url": "https://slow.example/archlinux/", "protocol": "https",
+         "last_sync": "2024-01-01T10:00:00Z", "completion_pct": 1.0, "delay": 3600,
+         "score": 5.5, "active": true, "country": "Germany", "country_code": "DE",
+         "ipv4": true, "ipv6": false},
+        {"url": "https://fast.example/archlinux/", "protocol": "https",
+         "last_sync": "2024-01-01T11:00:00Z", "completion_pct": 1.0, "delay": 60,
+         "score": 0.7, "active": true, "country": "Germany", "country_code": "DE",
+         "ipv4": true, "ipv6": true},
+        {"url": "https://stale.example/archlinux/", "protocol": "https",
+         "last_sync": null, "completion_pct": 0.5, "delay": null,
+         "score": null, "active": true, "country": "France", "country_code": "FR",
+         "ipv4": true, "ipv6": true},
+        {"url": "http://other.example/archlinux/", "protocol": "http",
+         "last_sync": "2024-01-01T11:00:00Z", "completion_pct": 1.0, "delay": 60,
+         "score": 0.5, "active": true, "country": "France", "country_code": "FR",
+         "ipv4": true, "ipv6": true}
+    ]}"#;
+
+    #[test]
+    fn select_mirrors() {
+        let query = MirrorStatusQuery::new().country("de");
+        let status: StatusResponse = serde_json::from_str(STATUS).unwrap();
+        let mirrors = query.select(status.urls);
+        let servers: Vec<String> = mirrors.iter().map(|m| m.server()).collect();
+        assert_eq!(
+            vec![
+                "https://fast.example/archlinux/$repo/os/$arch",
+                "https://slow.example/archlinux/$repo/os/$arch"
+            ],
+            servers
+        );
+        assert!(mirrors[0].last_sync.is_some());
+
+        let query = MirrorStatusQuery::new().protocol("https").limit(1);
+        let status: StatusResponse = serde_json::from_str(STATUS).unwrap();
+        assert_eq!(
+            "https://fast.example/archlinux/",
+            query.select(status.urls)[0].url
+        );
+    }
+}
@@ -0,0 +1,252 @@
+use chrono::{DateTime, TimeZone, Utc};
+use std::fmt::{Display, Formatter};
+
+/// Type of file described by mtree entry
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum MtreeEntryType {
+    File,
+    Dir,
+    Link,
+}
+
+/// File record of package `.MTREE`
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct MtreeEntry {
+    /// path relative to installation root, without leading `./`
+    pub path: String,
+    pub entry_type: MtreeEntryType,
+    /// permission bits
+    pub mode: u32,
+    pub uid: u32,
+    pub gid: u32,
+    /// file size in bytes
+    pub size: Option<u64>,
+    /// modification time
+    pub time: Option<DateTime<Utc>>,
+    pub md5_sum: Option<String>,
+    pub sha256_sum: Option<String>,
+    /// symlink target
+    pub link: Option<String>,
+}
+
+/// Error of mtree parsing
+#[derive(Clone, Debug, PartialEq)]
+pub enum MtreeError {
+    /// Keyword doesn't have `keyword=value` format or special command is unknown
+    InvalidLine(String),
+    /// Keyword value cannot be parsed
+    InvalidValue { keyword: String, value: String },
+}
+
+impl Display for MtreeError {
+    fn fmt(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        match self {
+            MtreeError::InvalidLine(line) => write!(formatter, "Invalid line {}", line),
+            MtreeError::InvalidValue { keyword, value } => {
+                write!(formatter, "Invalid value {} of keyword {}", value, keyword)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MtreeError {}
+
+/// Keywords which apply to following entries, set by `/set` command
+#[derive(Clone, Default)]
+struct Defaults {
+    entry_type: Option<MtreeEntryType>,
+    mode: Option<u32>,
+    uid: Option<u32>,
+    gid: Option<u32>,
+}
+
+/// Parse decompressed mtree file in format written by `bsdtar --format=mtree`, which is used by
+/// makepkg for `.MTREE` package member
+///
+/// # Example
+/// ```
+/// use archlinux_repo::{parse_mtree, MtreeEntryType};
+///
+/// let entries = parse_mtree("#mtree\n/set type=file uid=0 gid=0 mode=644\n\
+///     ./usr/bin/foo time=1560520506.0 mode=755 size=16\n").unwrap();
+/// assert_eq!("usr/bin/foo", entries[0].path);
+/// assert_eq!(MtreeEntryType::File, entries[0].entry_type);
+/// assert_eq!(0o755, entries[0].mode);
+/// ```
+pub fn parse_mtree(contents: &str) -> Result<Vec<MtreeEntry>, MtreeError> {
+    let mut defaults = Defaults::default();
+    let mut entries = Vec::new();
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut words = line.split_whitespace();
+        let first = words.next().unwrap();
+        match first {
+            "/set" => {
+                for word in words {
+                    let (keyword, value) = keyword(word)?;
+                    apply(&mut defaults, keyword, value)?;
+                }
+            }
+            "/unset" => {
+                for word in words {
+                    match word {
+                        "all" => defaults = Defaults::default(),
+                        "type" => defaults.entry_type = None,
+                        "mode" => defaults.mode = None,
+                        "uid" => defaults.uid = None,
+                        "gid" => defaults.gid = None,
+                        _ => {}
+                    }
+                }
+            }
+            _ if first.starts_with('/') => return Err(MtreeError::InvalidLine(line.to_owned())),
+            path => {
+                let mut attributes = defaults.clone();
+                let mut entry = MtreeEntry {
+                    path: unescape(path.strip_prefix("./").unwrap_or(path)),
+                    entry_type: MtreeEntryType::File,
+                    mode: 0,
+                    uid: 0,
+                    gid: 0,
+                    size: None,
+                    time: None,
+                    md5_sum: None,
+                    sha256_sum: None,
+                    link: None,
+                };
+                for word in words {
+                    let (keyword, value) = keyword(word)?;
+                    match keyword {
+                        "size" => entry.size = Some(parse(keyword, value)?),
+                        "time" => entry.time = Some(parse_time(value)?),
+                        "md5digest" => entry.md5_sum = Some(value.to_owned()),
+                        "sha256digest" => entry.sha256_sum = Some(value.to_owned()),
+                        "link" => entry.link = Some(unescape(value)),
+                        _ => apply(&mut attributes, keyword, value)?,
+                    }
+                }
+                entry.entry_type = attributes.entry_type.unwrap_or(MtreeEntryType::File);
+                entry.mode = attributes.mode.unwrap_or(0);
+                entry.uid = attributes.uid.unwrap_or(0);
+                entry.gid = attributes.gid.unwrap_or(0);
+                entries.push(entry);
+            }
+        }
+    }
+    Ok(entries)
+}
+
+fn keyword(word: &str) -> Result<(&str, &str), MtreeError> {
+    word.split_once('=')
+        .ok_or_else(|| MtreeError::InvalidLine(word.to_owned()))
+}
+
+/// Apply keyword which can be set by `/set` command. Unknown keywords are ignored
+fn apply(defaults: &mut Defaults, keyword: &str, value: &str) -> Result<(), MtreeError> {
+    match keyword {
+        "type" => {
+            defaults.entry_type = Some(match value {
+                "file" => MtreeEntryType::File,
+                "dir" => MtreeEntryType::Dir,
+                "link" => MtreeEntryType::Link,
+                _ => return Err(invalid(keyword, value)),
+            })
+        }
+        "mode" => {
+            defaults.mode =
+                Some(u32::from_str_radix(value, 8).map_err(|_| invalid(keyword, value))?)
+        }
+        "uid" => defaults.uid = Some(parse(keyword, value)?),
+        "gid" => defaults.gid = Some(parse(keyword, value)?),
+        _ => {}
+    }
+    Ok(())
+}
+
+fn parse<T: std::str::FromStr>(keyword: &str, value: &str) -> Result<T, MtreeError> {
+    value.parse().map_err(|_| invalid(keyword, value))
+}
+
+/// Parse `seconds.fraction` timestamp
+fn parse_time(value: &str) -> Result<DateTime<Utc>, MtreeError> {
+    let (seconds, nanoseconds) = value.split_once('.').unwrap_or((value, "0"));
+    if nanoseconds.len() > 9 {
+        return Err(invalid("time", value));
+    }
+    let seconds = parse("time", seconds)?;
+    let nanoseconds = parse::<u32>("time", nanoseconds)? * 10u32.pow(9 - nanoseconds.len() as u32);
+    Utc.timestamp_opt(seconds, nanoseconds)
+        .single()
+        .ok_or_else(|| invalid("time", value))
+}
+
+fn invalid(keyword: &str, value: &str) -> MtreeError {
+    MtreeError::InvalidValue {
+        keyword: keyword.to_owned(),
+        value: value.to_owned(),
+    }
+}
+
+/// Decode `\ooo` octal escapes used for whitespace and special characters in paths
+fn unescape(value: &str) -> String {
+    if !value.contains('\\') {
+        return value.to_owned();
+    }
+    let bytes = value.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escape = bytes.get(i + 1..i + 4).filter(|_| bytes[i] == b'\\');
+        match escape
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .and_then(|digits| u8::from_str_radix(digits, 8).ok())
+        {
+            Some(byte) => {
+                result.push(byte);
+                i += 4;
+            }
+            None => {
+                result.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&result).into_owned()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{parse_mtree, MtreeEntryType};
+
+    #[test]
+    fn parse_package_mtree() {
+        let entries = parse_mtree(
+            "#mtree\n\
+            /set type=file uid=0 gid=0 mode=644\n\
+            ./.PKGINFO time=1560520506.5 size=512 md5digest=d41d8cd98f00b204e9800998ecf8427e \
+            sha256digest=e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855\n\
+            ./usr time=1560520506.0 mode=755 type=dir\n\
+            ./usr/bin/foo\\040bar time=1560520506.0 mode=755 size=16\n\
+            ./usr/bin/baz time=1560520506.0 mode=777 type=link link=foo\\040bar\n\
+            /set uid=1000\n\
+            ./home time=1560520506.0 type=dir\n",
+        )
+        .unwrap();
+        assert_eq!(5, entries.len());
+        assert_eq!(".PKGINFO", entries[0].path);
+        assert_eq!(0o644, entries[0].mode);
+        assert_eq!(Some(512), entries[0].size);
+        assert_eq!(
+            500_000_000,
+            entries[0].time.unwrap().timestamp_subsec_nanos()
+        );
+        assert_eq!(MtreeEntryType::Dir, entries[1].entry_type);
+        assert_eq!("usr/bin/foo bar", entries[2].path);
+        assert_eq!(MtreeEntryType::File, entries[2].entry_type);
+        assert_eq!(Some("foo bar".to_owned()), entries[3].link);
+        assert_eq!(1000, entries[4].uid);
+    }
+}
@@ -0,0 +1,127 @@
+use crate::archive;
+use crate::http::{self, HttpClient, HttpResponse};
+use crate::{Inner, Progress, Repository, RepositoryBuilder, Stage};
+use chrono::{SubsecRound, Utc};
+use futures::future::BoxFuture;
+use std::error::Error;
+use std::io::Read;
+use std::sync::Arc;
+
+/// Client of repositories loaded without HTTP backend. Fails every request
+struct OfflineClient;
+
+impl HttpClient for OfflineClient {
+    fn get<'a>(&'a self, _: &'a str) -> BoxFuture<'a, Result<HttpResponse, Box<dyn Error>>> {
+        Box::pin(async {
+            Err("HTTP client is not set and no HTTP backend feature is enabled".into())
+        })
+    }
+}
+
+impl Repository {
+    /// Create repository with default settings from already downloaded database and optional
+    /// files metadata archives. Archives are read with the same pipeline as downloaded ones.
+    /// Repository has empty name and url, use [`RepositoryBuilder::load_archives`] to set them
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::Repository;
+    /// use std::fs::File;
+    ///
+    /// let repo = Repository::from_archive_readers(
+    ///     File::open("mingw64.db.tar.gz")?,
+    ///     Some(File::open("mingw64.files.tar.gz")?),
+    /// )?;
+    /// ```
+    pub fn from_archive_readers<D, F>(db: D, files: Option<F>) -> Result<Repository, Box<dyn Error>>
+    where
+        D: Read,
+        F: Read,
+    {
+        RepositoryBuilder::new("", "").load_archives(db, files)
+    }
+}
+
+impl RepositoryBuilder {
+    /// Create repository from already downloaded database and optional files metadata
+    /// archives instead of downloading them. Builder settings are used to index packages,
+    /// builder name and url are used for reloading and package downloads. Progress listener
+    /// receives reading progress
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::RepositoryBuilder;
+    /// use std::fs::File;
+    ///
+    /// let repo = RepositoryBuilder::new("mingw64", "http://repo.msys2.org/mingw/x86_64")
+    ///                 .load_archives(File::open("mingw64.db.tar.gz")?, None::<File>)?;
+    /// ```
+    pub fn load_archives<D, F>(
+        mut self,
+        db: D,
+        files: Option<F>,
+    ) -> Result<Repository, Box<dyn Error>>
+    where
+        D: Read,
+        F: Read,
+    {
+        self.mirrors = self.resolved_mirrors()?;
+        let progress = |stage: Stage| {
+            let listener = self.progress_listener.as_ref();
+            move |items_done, path| {
+                if let Some(l) = listener {
+                    l(Progress {
+                        items_done,
+                        current: Some(path),
+                        ..Progress::new(stage)
+                    })
+                }
+            }
+        };
+        let mut inner = Inner {
+            loaded_at: Utc::now().trunc_subsecs(0),
+            ..Inner::default()
+        };
+        let (packages, warnings) = archive::read_entries(
+            db,
+            Stage::ReadingDb,
+            &["/desc"],
+            &self.options,
+            archive::Descs::new(&self.options),
+            progress(Stage::ReadingDb),
+        )
+        .map_err(|e| e as Box<dyn Error>)?;
+        inner.warnings.extend(warnings);
+        inner.insert_all(packages, &self.options)?;
+        if let Some(metrics) = &self.options.metrics {
+            metrics.packages_loaded(&self.name, inner.packages.len() as u64);
+        }
+        self.report(Stage::DbDone);
+        if let Some(files) = files {
+            let entries = archive::read_entries(
+                files,
+                Stage::ReadingFilesMetadata,
+                &["/files"],
+                &self.options,
+                archive::Files::default(),
+                progress(Stage::ReadingFilesMetadata),
+            )
+            .map_err(|e| e as Box<dyn Error>)?;
+            inner.set_files(entries);
+            self.report(Stage::FilesMetadataDone);
+        }
+        inner.finish(&self.options)?;
+        inner.report_loaded(&self.name, &self.options);
+        let http_client = match self.http_client.clone() {
+            Some(client) => client,
+            None => http::default_client(&self.network)?.unwrap_or_else(|| Arc::new(OfflineClient)),
+        };
+        Ok(Repository::from_parts(self, http_client, inner))
+    }
+
+    fn report(&self, stage: Stage) {
+        if let Some(l) = self.progress_listener.as_ref() {
+            l(Progress::new(stage))
+        }
+    }
+}
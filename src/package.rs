@@ -0,0 +1,482 @@
+use crate::mtree::parse_mtree;
+use crate::sanitize::check_member_path;
+use crate::{BuildInfo, Dependency, HttpResponse, MtreeEntry};
+use chrono::{DateTime, TimeZone, Utc};
+use flate2::read::MultiGzDecoder;
+use futures::executor::block_on;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::str::FromStr;
+use tar::{Archive, Entries};
+
+/// Open decompressing reader for package archive. Compression is detected by magic bytes,
+/// zstd, xz and gzip are supported. Uncompressed tar is returned as is
+pub(crate) fn decompress<'a, R: Read + Send + 'a>(
+    reader: R,
+) -> std::io::Result<Box<dyn Read + Send + 'a>> {
+    let mut reader = BufReader::new(reader);
+    let magic = reader.fill_buf()?;
+    Ok(if magic.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+        Box::new(zstd::Decoder::with_buffer(reader)?)
+    } else if magic.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]) {
+        Box::new(xz2::read::XzDecoder::new(reader))
+    } else if magic.starts_with(&[0x1f, 0x8b]) {
+        Box::new(MultiGzDecoder::new(reader))
+    } else {
+        Box::new(reader)
+    })
+}
+
+/// Read member of package archive. Returns `None` if archive doesn't have it
+pub(crate) fn read_member<R: Read + Send>(
+    reader: R,
+    path: &str,
+) -> std::io::Result<Option<Vec<u8>>> {
+    let mut archive = Archive::new(decompress(reader)?);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if entry.path()?.as_os_str() == path {
+            let mut contents = Vec::with_capacity(entry.size() as usize);
+            entry.read_to_end(&mut contents)?;
+            return Ok(Some(contents));
+        }
+    }
+    Ok(None)
+}
+
+/// Read `.PKGINFO` from package archive. Returns `None` if archive doesn't have it
+pub(crate) fn read_pkginfo<R: Read + Send>(
+    reader: R,
+) -> Result<Option<PackageInfo>, PkgInfoReadError> {
+    match read_member(reader, ".PKGINFO")? {
+        Some(contents) => {
+            let contents = String::from_utf8(contents)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+            Ok(Some(PackageInfo::from_str(&contents)?))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Package file, like `foo-1.0-1-x86_64.pkg.tar.zst`. Archive may be compressed with zstd, xz
+/// or gzip
+///
+/// # Example
+/// ```ignore
+/// use archlinux_repo::{PackageArchive, Repository};
+///
+/// let repo = Repository::load("mingw64", "http://repo.msys2.org/mingw/x86_64").await?;
+/// let data = repo.request_package("mingw-w64-gtk3").await?.bytes().await?;
+/// PackageArchive::new(data).extract_to("/opt/sysroot")?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct PackageArchive {
+    data: Vec<u8>,
+}
+
+impl PackageArchive {
+    /// Create archive from package file contents
+    pub fn new(data: Vec<u8>) -> Self {
+        PackageArchive { data }
+    }
+
+    /// Read package file
+    pub fn open<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        Ok(PackageArchive::new(std::fs::read(path)?))
+    }
+
+    /// Package file contents
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Parse `.PKGINFO` member
+    pub fn pkginfo(&self) -> Result<PackageInfo, Box<dyn Error>> {
+        match read_pkginfo(self.data.as_slice()) {
+            Ok(Some(info)) => Ok(info),
+            Ok(None) => Err(".PKGINFO not found in package".into()),
+            Err(PkgInfoReadError::Io(e)) => Err(Box::new(e)),
+            Err(PkgInfoReadError::Parse(e)) => Err(Box::new(e)),
+        }
+    }
+
+    /// Parse `.BUILDINFO` member
+    pub fn buildinfo(&self) -> Result<BuildInfo, Box<dyn Error>> {
+        let contents = read_member(self.data.as_slice(), ".BUILDINFO")?
+            .ok_or(".BUILDINFO not found in package")?;
+        Ok(BuildInfo::from_str(std::str::from_utf8(&contents)?)?)
+    }
+
+    /// Parse `.MTREE` member, which lists package files with their attributes and checksums
+    pub fn mtree(&self) -> Result<Vec<MtreeEntry>, Box<dyn Error>> {
+        let contents =
+            read_member(self.data.as_slice(), ".MTREE")?.ok_or(".MTREE not found in package")?;
+        let mut text = String::new();
+        decompress(contents.as_slice())?.read_to_string(&mut text)?;
+        Ok(parse_mtree(&text)?)
+    }
+
+    /// Paths of package files in archive order, as they are listed in files database.
+    /// Directory paths end with `/`, metadata entries are not included
+    pub fn file_list(&self) -> std::io::Result<Vec<String>> {
+        let mut archive = Archive::new(decompress(self.data.as_slice())?);
+        let mut files = Vec::new();
+        for entry in archive.entries()? {
+            let entry = entry?;
+            let path = entry.path()?;
+            if is_metadata(&path) {
+                continue;
+            }
+            let mut path = path.to_string_lossy().into_owned();
+            if entry.header().entry_type().is_dir() && !path.ends_with('/') {
+                path.push('/');
+            }
+            files.push(path);
+        }
+        Ok(files)
+    }
+
+    /// Unpack package files into `prefix` directory like pacman does with `--root`. Symlinks
+    /// are recreated, permissions and modification times are preserved and existing files are
+    /// overwritten. Metadata entries in archive root (`.PKGINFO`, `.MTREE`, `.BUILDINFO`,
+    /// `.INSTALL` and others) are skipped. Fails with [`UnsafePathError`] if entry has
+    /// absolute path or `..` component, entries which would be placed outside of `prefix`
+    /// through symlinks are skipped
+    ///
+    /// [`UnsafePathError`]: crate::UnsafePathError
+    pub fn extract_to<P: AsRef<Path>>(&self, prefix: P) -> std::io::Result<()> {
+        let prefix = prefix.as_ref();
+        std::fs::create_dir_all(prefix)?;
+        let mut archive = Archive::new(decompress(self.data.as_slice())?);
+        archive.set_preserve_permissions(true);
+        archive.set_preserve_mtime(true);
+        archive.set_overwrite(true);
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let path = entry.path()?;
+            check_member_path(&path)?;
+            if is_metadata(&path) {
+                continue;
+            }
+            entry.unpack_in(prefix)?;
+        }
+        Ok(())
+    }
+}
+
+/// Decompressed tar archive streamed from package response, returned by
+/// [`Repository::open_package`](crate::Repository::open_package). Reading entries blocks
+/// current thread until next body chunk is downloaded, so use it in blocking context like
+/// `tokio::task::spawn_blocking`
+///
+/// # Example
+/// ```ignore
+/// use archlinux_repo::Repository;
+///
+/// let repo = Repository::load("mingw64", "http://repo.msys2.org/mingw/x86_64").await?;
+/// let mut package = repo.open_package("mingw-w64-gtk3").await?;
+/// tokio::task::spawn_blocking(move || -> std::io::Result<()> {
+///     for entry in package.entries()? {
+///         println!("{}", entry?.path()?.display());
+///     }
+///     Ok(())
+/// })
+/// .await??;
+/// ```
+pub struct PackageReader {
+    archive: Archive<Box<dyn Read + Send>>,
+}
+
+impl PackageReader {
+    /// Wait for first body chunk to detect compression without blocking
+    pub(crate) async fn new(mut response: HttpResponse) -> Result<Self, Box<dyn Error>> {
+        let chunk = response.chunk().await?.unwrap_or_default();
+        let body = BodyReader {
+            response,
+            chunk,
+            position: 0,
+        };
+        Ok(PackageReader {
+            archive: Archive::new(decompress(body)?),
+        })
+    }
+
+    /// Archive entries, including metadata ones like `.PKGINFO`. Can be called only once
+    pub fn entries(&mut self) -> std::io::Result<Entries<'_, Box<dyn Read + Send>>> {
+        self.archive.entries()
+    }
+
+    /// Underlying tar archive
+    pub fn into_archive(self) -> Archive<Box<dyn Read + Send>> {
+        self.archive
+    }
+}
+
+/// Blocking reader over response body
+struct BodyReader {
+    response: HttpResponse,
+    chunk: Vec<u8>,
+    position: usize,
+}
+
+impl Read for BodyReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.position == self.chunk.len() {
+            match block_on(self.response.chunk()) {
+                Ok(Some(chunk)) => {
+                    self.chunk = chunk;
+                    self.position = 0;
+                }
+                Ok(None) => return Ok(0),
+                Err(e) => return Err(std::io::Error::other(e.to_string())),
+            }
+        }
+        let read = buf.len().min(self.chunk.len() - self.position);
+        buf[..read].copy_from_slice(&self.chunk[self.position..self.position + read]);
+        self.position += read;
+        Ok(read)
+    }
+}
+
+/// Returns `true` for package metadata entries, which are dotfiles in archive root
+fn is_metadata(path: &Path) -> bool {
+    let mut components = path.components();
+    match (components.next(), components.next()) {
+        (Some(first), None) => first.as_os_str().to_string_lossy().starts_with('.'),
+        _ => false,
+    }
+}
+
+pub(crate) enum PkgInfoReadError {
+    Io(std::io::Error),
+    Parse(PackageInfoError),
+}
+
+impl From<std::io::Error> for PkgInfoReadError {
+    fn from(e: std::io::Error) -> Self {
+        PkgInfoReadError::Io(e)
+    }
+}
+
+impl From<PackageInfoError> for PkgInfoReadError {
+    fn from(e: PackageInfoError) -> Self {
+        PkgInfoReadError::Parse(e)
+    }
+}
+
+/// Error of `.PKGINFO` or `.BUILDINFO` parsing
+#[derive(Clone, Debug, PartialEq)]
+pub enum PackageInfoError {
+    /// Line is not a comment and doesn't have `key = value` format
+    InvalidLine(String),
+    /// Required field is not set
+    MissingField(&'static str),
+    /// Field value cannot be parsed
+    InvalidValue { field: String, value: String },
+}
+
+impl Display for PackageInfoError {
+    fn fmt(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        match self {
+            PackageInfoError::InvalidLine(line) => write!(formatter, "Invalid line {}", line),
+            PackageInfoError::MissingField(field) => {
+                write!(formatter, "Required field {} not found", field)
+            }
+            PackageInfoError::InvalidValue { field, value } => {
+                write!(formatter, "Invalid value {} of field {}", value, field)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PackageInfoError {}
+
+/// Package metadata from `.PKGINFO` member of package archive
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct PackageInfo {
+    /// name
+    pub name: String,
+    /// name without architecture
+    pub base: Option<String>,
+    /// version
+    pub version: String,
+    /// description
+    pub description: Option<String>,
+    /// package home url
+    pub url: Option<String>,
+    /// build date
+    pub build_date: Option<DateTime<Utc>>,
+    /// who created this package
+    pub packager: Option<String>,
+    /// installed files size
+    pub size: Option<u64>,
+    /// processor architecture
+    pub architecture: Option<String>,
+    /// license names
+    pub licenses: Vec<String>,
+    /// package groups
+    pub groups: Vec<String>,
+    /// packages which this package replaces
+    pub replaces: Vec<String>,
+    /// packages which cannot be used with this package
+    pub conflicts: Vec<String>,
+    /// packages provided by this package
+    pub provides: Vec<String>,
+    /// configuration files which are kept on upgrade
+    pub backup: Vec<String>,
+    /// run-time dependencies
+    pub depends: Vec<Dependency>,
+    /// optional dependencies with descriptions, like `foo: for bar support`
+    pub optdepends: Vec<String>,
+    /// build-time dependencies
+    pub makedepends: Vec<Dependency>,
+    pub checkdepends: Vec<Dependency>,
+    /// fields which are not listed above, in file order
+    pub extra: Vec<(String, String)>,
+}
+
+impl FromStr for PackageInfo {
+    type Err = PackageInfoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut info = PackageInfo::default();
+        for line in key_values(s) {
+            let (key, value) = line?;
+            let dependency =
+                |value: &str| Dependency::from_str(value).map_err(|_| invalid(key, value));
+            match key {
+                "pkgname" => info.name = value.to_owned(),
+                "pkgbase" => info.base = Some(value.to_owned()),
+                "pkgver" => info.version = value.to_owned(),
+                "pkgdesc" => info.description = Some(value.to_owned()),
+                "url" => info.url = Some(value.to_owned()),
+                "builddate" => info.build_date = Some(timestamp(key, value)?),
+                "packager" => info.packager = Some(value.to_owned()),
+                "size" => info.size = Some(value.parse().map_err(|_| invalid(key, value))?),
+                "arch" => info.architecture = Some(value.to_owned()),
+                "license" => info.licenses.push(value.to_owned()),
+                "group" => info.groups.push(value.to_owned()),
+                "replaces" => info.replaces.push(value.to_owned()),
+                "conflict" => info.conflicts.push(value.to_owned()),
+                "provides" => info.provides.push(value.to_owned()),
+                "backup" => info.backup.push(value.to_owned()),
+                "depend" => info.depends.push(dependency(value)?),
+                "optdepend" => info.optdepends.push(value.to_owned()),
+                "makedepend" => info.makedepends.push(dependency(value)?),
+                "checkdepend" => info.checkdepends.push(dependency(value)?),
+                _ => info.extra.push((key.to_owned(), value.to_owned())),
+            }
+        }
+        if info.name.is_empty() {
+            return Err(PackageInfoError::MissingField("pkgname"));
+        }
+        if info.version.is_empty() {
+            return Err(PackageInfoError::MissingField("pkgver"));
+        }
+        Ok(info)
+    }
+}
+
+/// Iterate over `key = value` lines of `.PKGINFO`-like files, skipping comments and empty lines
+pub(crate) fn key_values(s: &str) -> impl Iterator<Item = Result<(&str, &str), PackageInfoError>> {
+    s.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            line.split_once(" = ")
+                .ok_or_else(|| PackageInfoError::InvalidLine(line.to_owned()))
+        })
+}
+
+pub(crate) fn invalid(field: &str, value: &str) -> PackageInfoError {
+    PackageInfoError::InvalidValue {
+        field: field.to_owned(),
+        value: value.to_owned(),
+    }
+}
+
+/// Parse unix timestamp in seconds
+pub(crate) fn timestamp(field: &str, value: &str) -> Result<DateTime<Utc>, PackageInfoError> {
+    let seconds = value.parse().map_err(|_| invalid(field, value))?;
+    Utc.timestamp_opt(seconds, 0)
+        .single()
+        .ok_or_else(|| invalid(field, value))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{PackageArchive, PackageInfo};
+    use std::str::FromStr;
+
+    #[test]
+    #[cfg(unix)]
+    fn extract_package() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut append = |path: &str, mode: u32, contents: &[u8]| {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(mode);
+            header.set_cksum();
+            builder.append_data(&mut header, path, contents).unwrap();
+        };
+        append(".PKGINFO", 0o644, b"pkgname = foo\npkgver = 1.0-1\n");
+        append(".MTREE", 0o644, b"");
+        append("usr/bin/foo", 0o755, b"#!/bin/sh\n");
+        append("usr/share/foo/.hidden", 0o644, b"kept");
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Symlink);
+        header.set_size(0);
+        header.set_mode(0o777);
+        builder
+            .append_link(&mut header, "usr/bin/bar", "foo")
+            .unwrap();
+        let data = zstd::encode_all(builder.into_inner().unwrap().as_slice(), 3).unwrap();
+
+        let prefix = std::env::temp_dir().join("archlinux-repo-extract");
+        let _ = std::fs::remove_dir_all(&prefix);
+        let archive = PackageArchive::new(data);
+        assert_eq!("foo", archive.pkginfo().unwrap().name);
+        archive.extract_to(&prefix).unwrap();
+        let foo = std::fs::metadata(prefix.join("usr/bin/foo")).unwrap();
+        assert_eq!(0o755, foo.permissions().mode() & 0o777);
+        assert_eq!(
+            std::path::Path::new("foo"),
+            std::fs::read_link(prefix.join("usr/bin/bar")).unwrap()
+        );
+        assert!(prefix.join("usr/share/foo/.hidden").exists());
+        assert!(!prefix.join(".PKGINFO").exists());
+        assert!(!prefix.join(".MTREE").exists());
+    }
+
+    #[test]
+    fn parse_pkginfo() {
+        let info = PackageInfo::from_str(
+            "# Generated by makepkg 6.0.1\n\
+            pkgname = foo\n\
+            pkgbase = foo\n\
+            xdata = pkgtype=pkg\n\
+            pkgver = 1.0-1\n\
+            builddate = 1560520506\n\
+            size = 4096\n\
+            license = MIT\n\
+            license = Apache\n\
+            depend = bar>=2.0\n\
+            optdepend = baz: for baz support\n",
+        )
+        .unwrap();
+        assert_eq!("foo", info.name);
+        assert_eq!("1.0-1", info.version);
+        assert_eq!(Some(4096), info.size);
+        assert_eq!(vec!["MIT", "Apache"], info.licenses);
+        assert_eq!("bar", &*info.depends[0].name);
+        assert_eq!(vec!["baz: for baz support"], info.optdepends);
+        assert_eq!(
+            vec![("xdata".to_owned(), "pkgtype=pkg".to_owned())],
+            info.extra
+        );
+    }
+}
@@ -0,0 +1,292 @@
+use crate::validate::package_violations;
+use crate::{Dependency, Package, PackageCompression, Violation};
+use chrono::{DateTime, SubsecRound, Utc};
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+
+/// Returned by [`PackageBuilder::build`] when package is incomplete or inconsistent
+#[derive(Clone, Debug, PartialEq)]
+pub enum PackageBuildError {
+    /// name, version or architecture is empty
+    MissingField(&'static str),
+    /// package violates invariants checked by [`Repository::validate`]
+    ///
+    /// [`Repository::validate`]: crate::Repository::validate
+    Invalid(Vec<Violation>),
+}
+
+impl Display for PackageBuildError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PackageBuildError::MissingField(field) => write!(f, "{} is not set", field),
+            PackageBuildError::Invalid(violations) => {
+                write!(f, "invalid package: {}", violations[0])
+            }
+        }
+    }
+}
+
+impl std::error::Error for PackageBuildError {}
+
+/// Builder of [`Package`] for creating packages programmatically
+///
+/// # Example
+/// ```
+/// use archlinux_repo::PackageBuilder;
+///
+/// let package = PackageBuilder::new("foo", "1.0-1", "x86_64")
+///     .description("Foo tool")
+///     .compressed_size(1024)
+///     .installed_size(4096)
+///     .sha256_sum("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+///     .depends("glibc>=2.38".parse().unwrap())
+///     .build()
+///     .unwrap();
+/// assert_eq!("foo-1.0-1-x86_64.pkg.tar.zst", package.file_name);
+/// ```
+#[derive(Clone, Debug)]
+pub struct PackageBuilder {
+    package: Package,
+    file_name: Option<String>,
+    pub(crate) compression: PackageCompression,
+}
+
+impl PackageBuilder {
+    /// Create builder of package with name, full version and architecture. Build date is set
+    /// to current time and packager to `Unknown Packager`, like makepkg does
+    pub fn new(name: &str, version: &str, architecture: &str) -> Self {
+        PackageBuilder {
+            package: Package {
+                file_name: String::new(),
+                name: name.to_owned(),
+                base: None,
+                version: version.to_owned(),
+                description: None,
+                groups: None,
+                compressed_size: 0,
+                installed_size: 0,
+                md5_sum: String::new(),
+                sha256_sum: String::new(),
+                pgp_signature: String::new(),
+                home_url: None,
+                license: None,
+                architecture: Arc::from(architecture),
+                build_date: Utc::now().trunc_subsecs(0),
+                packager: Arc::from("Unknown Packager"),
+                replaces: None,
+                conflicts: None,
+                provides: None,
+                depends: None,
+                optdepends: None,
+                makedepends: None,
+                checkdepends: None,
+                deltas: None,
+                linked_sources: Vec::new(),
+                raw_desc: None,
+            },
+            file_name: None,
+            compression: PackageCompression::default(),
+        }
+    }
+
+    /// Set package file name. Defaults to `name-version-arch.pkg.tar.zst`, see
+    /// [`PackageBuilder::compression`]
+    pub fn file_name(mut self, file_name: &str) -> Self {
+        self.file_name = Some(file_name.to_owned());
+        self
+    }
+
+    /// Set base name
+    pub fn base(mut self, base: &str) -> Self {
+        self.package.base = Some(base.to_owned());
+        self
+    }
+
+    /// Set description
+    pub fn description(mut self, description: &str) -> Self {
+        self.package.description = Some(description.to_owned());
+        self
+    }
+
+    /// Set package file size
+    pub fn compressed_size(mut self, size: u64) -> Self {
+        self.package.compressed_size = size;
+        self
+    }
+
+    /// Set installed files size
+    pub fn installed_size(mut self, size: u64) -> Self {
+        self.package.installed_size = size;
+        self
+    }
+
+    /// Set MD5 checksum of package file. Optional
+    pub fn md5_sum(mut self, sum: &str) -> Self {
+        self.package.md5_sum = sum.to_owned();
+        self
+    }
+
+    /// Set SHA256 checksum of package file
+    pub fn sha256_sum(mut self, sum: &str) -> Self {
+        self.package.sha256_sum = sum.to_owned();
+        self
+    }
+
+    /// Set base64-encoded PGP signature. Optional
+    pub fn pgp_signature(mut self, signature: &str) -> Self {
+        self.package.pgp_signature = signature.to_owned();
+        self
+    }
+
+    /// Set home url
+    pub fn home_url(mut self, url: &str) -> Self {
+        self.package.home_url = Some(url.to_owned());
+        self
+    }
+
+    /// Set build date
+    pub fn build_date(mut self, date: DateTime<Utc>) -> Self {
+        self.package.build_date = date;
+        self
+    }
+
+    /// Set packager
+    pub fn packager(mut self, packager: &str) -> Self {
+        self.package.packager = Arc::from(packager);
+        self
+    }
+
+    /// Add license
+    pub fn license(mut self, license: &str) -> Self {
+        push(&mut self.package.license, Arc::from(license));
+        self
+    }
+
+    /// Add group
+    pub fn group(mut self, group: &str) -> Self {
+        push(&mut self.package.groups, Arc::from(group));
+        self
+    }
+
+    /// Add replaced package
+    pub fn replaces(mut self, name: &str) -> Self {
+        push(&mut self.package.replaces, name.to_owned());
+        self
+    }
+
+    /// Add conflicting package
+    pub fn conflicts(mut self, name: &str) -> Self {
+        push(&mut self.package.conflicts, name.to_owned());
+        self
+    }
+
+    /// Add provision, like `sh` or `libfoo.so=1-64`
+    pub fn provides(mut self, provision: &str) -> Self {
+        push(&mut self.package.provides, provision.to_owned());
+        self
+    }
+
+    /// Add run-time dependency
+    pub fn depends(mut self, dependency: Dependency) -> Self {
+        push(&mut self.package.depends, dependency);
+        self
+    }
+
+    /// Add optional dependency
+    pub fn optdepends(mut self, dependency: Dependency) -> Self {
+        push(&mut self.package.optdepends, dependency);
+        self
+    }
+
+    /// Add build-time dependency
+    pub fn makedepends(mut self, dependency: Dependency) -> Self {
+        push(&mut self.package.makedepends, dependency);
+        self
+    }
+
+    /// Add test dependency
+    pub fn checkdepends(mut self, dependency: Dependency) -> Self {
+        push(&mut self.package.checkdepends, dependency);
+        self
+    }
+
+    /// Create package. Fails with [`PackageBuildError`] if name, version or architecture is
+    /// empty or package violates invariants checked by [`Repository::validate`]. MD5 checksum
+    /// is checked only if it is set
+    ///
+    /// [`Repository::validate`]: crate::Repository::validate
+    pub fn build(self) -> Result<Package, PackageBuildError> {
+        let mut package = self.package;
+        for (field, value) in [
+            ("name", package.name.as_str()),
+            ("version", package.version.as_str()),
+            ("architecture", &package.architecture),
+        ] {
+            if value.is_empty() {
+                return Err(PackageBuildError::MissingField(field));
+            }
+        }
+        let extension = self.compression.extension();
+        package.file_name = self.file_name.unwrap_or_else(|| {
+            format!(
+                "{}-{}-{}{}",
+                package.name, package.version, package.architecture, extension
+            )
+        });
+        let violations: Vec<Violation> = package_violations(&package, Utc::now())
+            .into_iter()
+            .filter(|v| {
+                !matches!(v, Violation::InvalidDigest { field: "MD5SUM", value } if value.is_empty())
+            })
+            .collect();
+        if !violations.is_empty() {
+            return Err(PackageBuildError::Invalid(violations));
+        }
+        Ok(package)
+    }
+}
+
+fn push<T>(values: &mut Option<Vec<T>>, value: T) {
+    values.get_or_insert_with(Vec::new).push(value)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{PackageBuildError, PackageBuilder, Violation};
+
+    const SHA256: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+    #[test]
+    fn package_is_validated() {
+        let package = PackageBuilder::new("foo", "1.0-1", "any")
+            .compressed_size(1)
+            .sha256_sum(SHA256)
+            .md5_sum("d41d8cd98f00b204e9800998ecf8427e")
+            .pgp_signature("c2lnbmF0dXJl")
+            .provides("libfoo.so=1-64")
+            .build()
+            .unwrap();
+        assert_eq!("foo-1.0-1-any.pkg.tar.zst", package.file_name);
+        assert_eq!(["libfoo.so=1-64"], package.provides());
+        let parsed = archlinux_repo_parser::from_str(&package.to_desc_string().unwrap()).unwrap();
+        assert_eq!(package, parsed);
+
+        assert_eq!(
+            Err(PackageBuildError::MissingField("version")),
+            PackageBuilder::new("foo", "", "any").build()
+        );
+        let error = PackageBuilder::new("foo", "1.0-1", "any")
+            .compressed_size(1)
+            .sha256_sum("abc")
+            .file_name("bar-1.0-1-any.pkg.tar.zst")
+            .build()
+            .unwrap_err();
+        match error {
+            PackageBuildError::Invalid(violations) => {
+                assert_eq!(2, violations.len());
+                assert!(matches!(violations[1], Violation::FileNameMismatch { .. }));
+            }
+            error => panic!("unexpected error {}", error),
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use crate::{HttpResponse, Package, Repository};
+use std::error::Error;
+use std::ops::Deref;
+
+/// Package together with repository it belongs to. Dereferences to [`Package`]
+///
+/// # Example
+/// ```ignore
+/// use archlinux_repo::Repository;
+///
+/// let repo = Repository::load("mingw64", "http://repo.msys2.org/mingw/x86_64").await?;
+/// let gtk = repo.package("mingw-w64-x86_64-gtk3").unwrap();
+/// println!("{} is downloaded from {}", gtk.name, gtk.url());
+/// for package in gtk.required_by() {
+///     println!("required by {}", package.name);
+/// }
+/// let contents = gtk.download().await?.bytes().await?;
+/// ```
+#[derive(Clone, Copy)]
+pub struct PackageRef<'a> {
+    repository: &'a Repository,
+    package: &'a Package,
+}
+
+impl<'a> PackageRef<'a> {
+    /// Package data
+    pub fn package(&self) -> &'a Package {
+        self.package
+    }
+
+    /// Repository which contains package
+    pub fn repository(&self) -> &'a Repository {
+        self.repository
+    }
+
+    /// Url of package file
+    pub fn url(&self) -> String {
+        self.repository.package_url(self.package)
+    }
+
+    /// Package files. Will return `None` if files metadata is not loaded,
+    /// see [`Repository::load_files_metadata`]
+    pub fn files(&self) -> Option<&'a Vec<String>> {
+        self.repository.get_package_files(&self.package.name)
+    }
+
+    /// Base64-encoded PGP signature of package file from repository database. Will return `None`
+    /// if database doesn't have it
+    pub fn signature(&self) -> Option<&'a str> {
+        Some(self.package.pgp_signature.as_str()).filter(|s| !s.is_empty())
+    }
+
+    /// Send HTTP request to download package file, see [`Repository::request_package`]
+    pub async fn download(&self) -> Result<HttpResponse, Box<dyn Error>> {
+        self.repository.request_package_file(self.package).await
+    }
+
+    /// Send HTTP request to download detached signature of package file, see
+    /// [`Repository::request_package_signature`]
+    pub async fn download_signature(&self) -> Result<HttpResponse, Box<dyn Error>> {
+        self.repository.request_signature_file(self.package).await
+    }
+
+    /// Packages of the same repository which have this package, or one of names it provides,
+    /// in their run-time dependencies. See [`Repository::dependents`]
+    pub fn required_by(&self) -> Vec<PackageRef<'a>> {
+        let provides = self
+            .package
+            .provides
+            .iter()
+            .flatten()
+            .map(|p| p.split('=').next().unwrap_or(p));
+        let mut packages: Vec<&'a Package> = Vec::new();
+        for name in std::iter::once(self.package.name.as_str()).chain(provides) {
+            for package in self.repository.dependents(name) {
+                if !packages.iter().any(|p| std::ptr::eq(*p, package)) {
+                    packages.push(package);
+                }
+            }
+        }
+        packages
+            .into_iter()
+            .map(|package| PackageRef {
+                repository: self.repository,
+                package,
+            })
+            .collect()
+    }
+}
+
+impl Deref for PackageRef<'_> {
+    type Target = Package;
+
+    fn deref(&self) -> &Self::Target {
+        self.package
+    }
+}
+
+impl Repository {
+    /// Get package handle by full name, name with version or base name, in the same order as
+    /// [`Repository::get_package_by_name`], [`Repository::get_package_by_name_and_version`] and
+    /// [`Repository::get_packages_by_base`]. Will return `None` if package cannot be found
+    pub fn package(&self, name: &str) -> Option<PackageRef<'_>> {
+        let package = self
+            .get_package_by_name(name)
+            .or_else(|| self.get_package_by_name_and_version(name))
+            .or_else(|| self.get_packages_by_base(name).into_iter().next())?;
+        Some(PackageRef {
+            repository: self,
+            package,
+        })
+    }
+
+    /// Iterate over package handles
+    pub fn package_refs(&self) -> impl Iterator<Item = PackageRef<'_>> {
+        self.into_iter().map(move |package| PackageRef {
+            repository: self,
+            package,
+        })
+    }
+}
@@ -0,0 +1,208 @@
+use crate::{Dependency, LocalDatabase, Package, Repository};
+use std::collections::{HashSet, VecDeque};
+use std::fmt::Display;
+
+/// Returned when install plan cannot be built because package or dependency is not
+/// provided by any repository package
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnresolvedDependencyError {
+    dependency: String,
+    required_by: Option<String>,
+}
+
+impl UnresolvedDependencyError {
+    /// Name of package or dependency which cannot be resolved
+    pub fn dependency(&self) -> &str {
+        &self.dependency
+    }
+
+    /// Package which requires dependency, `None` if requested package is not found
+    pub fn required_by(&self) -> Option<&str> {
+        self.required_by.as_deref()
+    }
+}
+
+impl Display for UnresolvedDependencyError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.required_by {
+            Some(package) => write!(
+                formatter,
+                "Dependency {} of {} cannot be resolved",
+                self.dependency, package
+            ),
+            None => write!(formatter, "Package {} not found", self.dependency),
+        }
+    }
+}
+
+impl std::error::Error for UnresolvedDependencyError {}
+
+/// Packages which should be installed to install requested packages with their run-time
+/// dependencies, see [`Repository::install_plan`]
+#[derive(Clone, Debug)]
+pub struct InstallPlan<'a> {
+    packages: Vec<&'a Package>,
+}
+
+impl<'a> InstallPlan<'a> {
+    /// Requested packages followed by dependencies which are not installed yet, in
+    /// breadth-first order
+    pub fn packages(&self) -> &[&'a Package] {
+        &self.packages
+    }
+
+    /// Total size of package files to download
+    pub fn total_download_size(&self) -> u64 {
+        self.packages.iter().map(|p| p.compressed_size).sum()
+    }
+
+    /// Total size of installed package files
+    pub fn total_installed_size(&self) -> u64 {
+        self.packages.iter().map(|p| p.installed_size).sum()
+    }
+}
+
+impl Repository {
+    /// Resolve run-time dependencies of packages by full name/base name or name with version.
+    /// Dependency is satisfied by package with the same name or, if there is no such package,
+    /// by first package which provides it, version constraints are checked with epochs
+    /// respected. If local database is provided, dependencies satisfied by installed packages
+    /// or their provisions are skipped, outdated installed packages are planned for upgrade.
+    /// Requested packages are always included. Fails with [`UnresolvedDependencyError`] if
+    /// package or dependency cannot be found
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::{LocalDatabase, Repository};
+    ///
+    /// let repo = Repository::load("mingw64", "http://repo.msys2.org/mingw/x86_64").await?;
+    /// let local = LocalDatabase::open(LocalDatabase::DEFAULT_PATH)?;
+    /// let plan = repo.install_plan(&["mingw-w64-x86_64-gtk3"], Some(&local))?;
+    /// println!("Total Download Size: {}", plan.total_download_size());
+    /// println!("Total Installed Size: {}", plan.total_installed_size());
+    /// ```
+    pub fn install_plan(
+        &self,
+        names: &[&str],
+        local: Option<&LocalDatabase>,
+    ) -> Result<InstallPlan<'_>, UnresolvedDependencyError> {
+        let requested = names
+            .iter()
+            .map(|name| self.inner.get(name).ok_or_else(|| not_found(name)))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.plan(requested, local)
+    }
+
+    /// Like [`Repository::install_plan`], but names which are not package names are resolved
+    /// as groups, like `base-devel`. Group members sorted by name are passed to `select`,
+    /// which returns members to install, like pacman's interactive selection. Use
+    /// `|_, members| members.to_vec()` to install all members. Fails with
+    /// [`UnresolvedDependencyError`] if name is neither package nor group or nothing is
+    /// selected from group
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::Repository;
+    ///
+    /// let repo = Repository::load("core", "https://geo.mirror.pkgbuild.com/core/os/x86_64").await?;
+    /// let plan = repo.install_plan_with_groups(&["base-devel", "git"], None, |_, members| {
+    ///     members.iter().copied().filter(|p| p.name != "sudo").collect()
+    /// })?;
+    /// ```
+    pub fn install_plan_with_groups<'a, F>(
+        &'a self,
+        names: &[&str],
+        local: Option<&LocalDatabase>,
+        mut select: F,
+    ) -> Result<InstallPlan<'a>, UnresolvedDependencyError>
+    where
+        F: FnMut(&str, &[&'a Package]) -> Vec<&'a Package>,
+    {
+        let mut requested = Vec::new();
+        for name in names {
+            if let Some(package) = self.inner.get(name) {
+                requested.push(package);
+                continue;
+            }
+            let members = self.group_members(name);
+            if members.is_empty() {
+                return Err(not_found(name));
+            }
+            let selected = select(name, &members);
+            if selected.is_empty() {
+                return Err(not_found(name));
+            }
+            requested.extend(selected);
+        }
+        self.plan(requested, local)
+    }
+
+    /// Packages which belong to group, sorted by name
+    pub fn group_members(&self, group: &str) -> Vec<&Package> {
+        let mut members: Vec<&Package> = self
+            .inner
+            .packages
+            .iter()
+            .filter(|p| !self.inner.synthetic.contains(&p.name))
+            .filter(|p| p.groups().iter().any(|g| &**g == group))
+            .map(|p| p.as_ref())
+            .collect();
+        members.sort_by(|a, b| a.name.cmp(&b.name));
+        members
+    }
+
+    /// Requested packages followed by their missing dependencies
+    fn plan<'a>(
+        &'a self,
+        requested: Vec<&'a Package>,
+        local: Option<&LocalDatabase>,
+    ) -> Result<InstallPlan<'a>, UnresolvedDependencyError> {
+        let mut packages: Vec<&Package> = Vec::new();
+        let mut planned = HashSet::new();
+        let mut queue = VecDeque::new();
+        for package in requested {
+            if planned.insert(package.name.as_str()) {
+                packages.push(package);
+                queue.push_back(package);
+            }
+        }
+        while let Some(package) = queue.pop_front() {
+            for dependency in package.depends.iter().flatten() {
+                if local.is_some_and(|local| is_installed(local, dependency)) {
+                    continue;
+                }
+                let providers = self.providers(&dependency.name);
+                let provider = match providers.iter().find(|p| dependency.is_satisfied_by(p)) {
+                    Some(provider) => *provider,
+                    None => {
+                        return Err(UnresolvedDependencyError {
+                            dependency: dependency.to_string(),
+                            required_by: Some(package.name.clone()),
+                        })
+                    }
+                };
+                if planned.insert(provider.name.as_str()) {
+                    packages.push(provider);
+                    queue.push_back(provider);
+                }
+            }
+        }
+        Ok(InstallPlan { packages })
+    }
+}
+
+fn not_found(name: &str) -> UnresolvedDependencyError {
+    UnresolvedDependencyError {
+        dependency: name.to_owned(),
+        required_by: None,
+    }
+}
+
+/// Returns `true` if installed package or its provision satisfies dependency with its
+/// version constraint
+fn is_installed(local: &LocalDatabase, dependency: &Dependency) -> bool {
+    local
+        .packages()
+        .iter()
+        .any(|p| dependency.is_satisfied_by_parts(&p.name, &p.version, p.provides.as_deref()))
+}
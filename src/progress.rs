@@ -0,0 +1,189 @@
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+
+/// Loading stage. Serialized as snake_case name, like `loading_db`. Discriminants are stable,
+/// so `stage as u8` can be used as numeric stage code
+#[derive(Serialize, Clone, Copy, Eq, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    /// Downloading repository database
+    LoadingDb = 0,
+    /// Reading package entries from repository database
+    ReadingDb = 1,
+    /// Database loaded
+    DbDone = 2,
+    /// Downloading files metadata
+    LoadingFilesMetadata = 3,
+    /// Reading package entries from files metadata
+    ReadingFilesMetadata = 4,
+    /// Files metadata loaded
+    FilesMetadataDone = 5,
+}
+
+/// Loading progress
+///
+/// # Example
+/// ```ignore
+/// use archlinux_repo::RepositoryBuilder;
+///
+/// RepositoryBuilder::new("mingw64", "http://repo.msys2.org/mingw/x86_64")
+///     .progress_listener(Box::new(|p| {
+///         if let Some(percent) = p.percent() {
+///             println!("{:?}: {:.1}%", p.stage, percent)
+///         }
+///     }))
+///     .load()
+///     .await?;
+/// ```
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Progress {
+    /// current stage
+    pub stage: Stage,
+    /// entries read in current stage
+    pub items_done: u64,
+    /// total entries in current stage, if known
+    pub items_total: Option<u64>,
+    /// bytes processed in current stage
+    pub bytes_done: u64,
+    /// total bytes in current stage, if known
+    pub bytes_total: Option<u64>,
+    /// name of file which is processed now
+    pub current: Option<String>,
+}
+
+impl Progress {
+    pub(crate) fn new(stage: Stage) -> Self {
+        Progress {
+            stage,
+            items_done: 0,
+            items_total: None,
+            bytes_done: 0,
+            bytes_total: None,
+            current: None,
+        }
+    }
+
+    pub(crate) fn bytes(stage: Stage, done: u64, total: Option<u64>) -> Self {
+        Progress {
+            bytes_done: done,
+            bytes_total: total,
+            ..Progress::new(stage)
+        }
+    }
+
+    /// Completion percentage of current stage in range `0.0..=100.0`.
+    /// Byte counters are preferred over item counters. Returns `None` if totals are unknown
+    pub fn percent(&self) -> Option<f64> {
+        match self.stage {
+            Stage::DbDone | Stage::FilesMetadataDone => Some(100.0),
+            _ => {
+                let (done, total) = match (self.bytes_total, self.items_total) {
+                    (Some(total), _) => (self.bytes_done, total),
+                    (None, Some(total)) => (self.items_done, total),
+                    (None, None) => return None,
+                };
+                if total == 0 {
+                    Some(100.0)
+                } else {
+                    Some((done as f64 / total as f64 * 100.0).min(100.0))
+                }
+            }
+        }
+    }
+}
+
+impl Display for Progress {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let current = self.current.as_deref().unwrap_or_default();
+        match self.stage {
+            Stage::LoadingDb => match (self.bytes_done, self.bytes_total) {
+                (0, _) => write!(f, "Loading repository database"),
+                (done, Some(total)) => {
+                    write!(f, "Loading repository: {} of {} bytes", done, total)
+                }
+                (done, None) => write!(f, "Loading repository: {} bytes", done),
+            },
+            Stage::ReadingDb => write!(f, "Loading repository file: {}", current),
+            Stage::DbDone => write!(f, "Database loaded"),
+            Stage::LoadingFilesMetadata => match (self.bytes_done, self.bytes_total) {
+                (0, _) => write!(f, "Loading files metadata"),
+                (done, Some(total)) => {
+                    write!(f, "Loading files metadata: {} of {} bytes", done, total)
+                }
+                (done, None) => write!(f, "Loading files metadata: {} bytes", done),
+            },
+            Stage::ReadingFilesMetadata => {
+                write!(f, "Loading files metadata file: {}", current)
+            }
+            Stage::FilesMetadataDone => write!(f, "Files metadata loaded"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Progress, Stage};
+
+    #[test]
+    fn percent_from_bytes() {
+        let progress = Progress::bytes(Stage::LoadingDb, 50, Some(200));
+        assert_eq!(Some(25.0), progress.percent());
+    }
+
+    #[test]
+    fn percent_from_items() {
+        let progress = Progress {
+            items_done: 3,
+            items_total: Some(4),
+            ..Progress::new(Stage::ReadingDb)
+        };
+        assert_eq!(Some(75.0), progress.percent());
+    }
+
+    #[test]
+    fn percent_unknown_without_totals() {
+        let progress = Progress::bytes(Stage::LoadingFilesMetadata, 50, None);
+        assert_eq!(None, progress.percent());
+    }
+
+    #[test]
+    fn percent_of_done_stage() {
+        assert_eq!(Some(100.0), Progress::new(Stage::DbDone).percent());
+    }
+
+    #[test]
+    fn progress_is_serialized() {
+        let progress = Progress {
+            current: Some("foo-1.0-1/desc".to_owned()),
+            ..Progress::bytes(Stage::ReadingDb, 10, Some(20))
+        };
+        assert_eq!(
+            serde_json::json!({
+                "stage": "reading_db",
+                "items_done": 0,
+                "items_total": null,
+                "bytes_done": 10,
+                "bytes_total": 20,
+                "current": "foo-1.0-1/desc",
+            }),
+            serde_json::to_value(&progress).unwrap()
+        );
+        assert_eq!(5, Stage::FilesMetadataDone as u8);
+    }
+
+    #[test]
+    fn display_is_compatible() {
+        assert_eq!(
+            "Loading repository database",
+            Progress::new(Stage::LoadingDb).to_string()
+        );
+        assert_eq!(
+            "Loading repository: 10 of 20 bytes",
+            Progress::bytes(Stage::LoadingDb, 10, Some(20)).to_string()
+        );
+        assert_eq!(
+            "Loading files metadata: 10 bytes",
+            Progress::bytes(Stage::LoadingFilesMetadata, 10, None).to_string()
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use crate::relations::provision_name;
+use crate::{Package, Repository};
+use std::fmt::{Display, Formatter};
+
+/// Shared library provision like `libfoo.so=1.2-64`, where `1.2` is soname version and `64`
+/// is architecture bitness
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct SonameProvision {
+    /// library name, like `libfoo.so`
+    pub name: String,
+    /// soname version, like `1.2`
+    pub version: Option<String>,
+    /// architecture bitness, like `64`
+    pub bits: Option<u8>,
+}
+
+impl SonameProvision {
+    /// First segment of soname version, like `1` for `libfoo.so=1.2-64`
+    pub fn major(&self) -> Option<&str> {
+        self.version
+            .as_deref()
+            .and_then(|version| version.split('.').next())
+    }
+}
+
+impl Display for SonameProvision {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)?;
+        if let Some(version) = &self.version {
+            write!(f, "={}", version)?;
+        }
+        if let Some(bits) = self.bits {
+            write!(f, "-{}", bits)?;
+        }
+        Ok(())
+    }
+}
+
+/// Entry of package `%PROVIDES%` list
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum Provision {
+    /// shared library, see [`SonameProvision`]
+    Soname(SonameProvision),
+    /// virtual package name with optional version, like `sh` or `java-runtime=17`
+    Package {
+        name: String,
+        version: Option<String>,
+    },
+}
+
+impl Provision {
+    /// Parse provision. Names which contain `.so` are parsed as sonames, their version suffix
+    /// after last `-` is parsed as bitness if it is a number
+    ///
+    /// # Example
+    /// ```
+    /// use archlinux_repo::{Provision, SonameProvision};
+    ///
+    /// let provision = Provision::parse("libfoo.so=1.2-64");
+    /// assert_eq!(
+    ///     Provision::Soname(SonameProvision {
+    ///         name: "libfoo.so".to_owned(),
+    ///         version: Some("1.2".to_owned()),
+    ///         bits: Some(64),
+    ///     }),
+    ///     provision
+    /// );
+    /// ```
+    pub fn parse(provision: &str) -> Self {
+        let (name, version) = match provision.split_once('=') {
+            Some((name, version)) => (name, Some(version)),
+            None => (provision, None),
+        };
+        if !is_soname(name) {
+            return Provision::Package {
+                name: name.to_owned(),
+                version: version.map(str::to_owned),
+            };
+        }
+        let (version, bits) = match version.and_then(|v| v.rsplit_once('-')) {
+            Some((version, bits)) if bits.parse::<u8>().is_ok() => {
+                (Some(version), bits.parse().ok())
+            }
+            _ => (version, None),
+        };
+        Provision::Soname(SonameProvision {
+            name: name.to_owned(),
+            version: version.map(str::to_owned),
+            bits,
+        })
+    }
+
+    /// Provided name
+    pub fn name(&self) -> &str {
+        match self {
+            Provision::Soname(soname) => &soname.name,
+            Provision::Package { name, .. } => name,
+        }
+    }
+}
+
+impl Display for Provision {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Provision::Soname(soname) => soname.fmt(f),
+            Provision::Package { name, version } => match version {
+                Some(version) => write!(f, "{}={}", name, version),
+                None => f.write_str(name),
+            },
+        }
+    }
+}
+
+/// Returns `true` for library names like `libfoo.so` or `libfoo.so.1`
+fn is_soname(name: &str) -> bool {
+    name.ends_with(".so") || name.contains(".so.")
+}
+
+impl Package {
+    /// Parsed `%PROVIDES%` entries
+    pub fn provisions(&self) -> Vec<Provision> {
+        self.provides
+            .iter()
+            .flatten()
+            .map(|provision| Provision::parse(provision))
+            .collect()
+    }
+}
+
+impl Repository {
+    /// Packages which provide shared library `name`, like `libfoo.so`. If `major` is set, only
+    /// packages which provide soname with this major version are returned. Uses provider index
+    /// if it is built with [`RepositoryBuilder::index_relations`]
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::Repository;
+    ///
+    /// let repo = Repository::load("core", "https://geo.mirror.pkgbuild.com/core/os/x86_64").await?;
+    /// for package in repo.soname_providers("libssl.so", Some("3")) {
+    ///     println!("{}", package.name);
+    /// }
+    /// ```
+    ///
+    /// [`RepositoryBuilder::index_relations`]: crate::RepositoryBuilder::index_relations
+    pub fn soname_providers(&self, name: &str, major: Option<&str>) -> Vec<&Package> {
+        self.providers(name)
+            .into_iter()
+            .filter(|package| {
+                package.provides.iter().flatten().any(|provision| {
+                    provision_name(provision) == name
+                        && match Provision::parse(provision) {
+                            Provision::Soname(soname) => major.is_none() || soname.major() == major,
+                            Provision::Package { .. } => false,
+                        }
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Provision, SonameProvision};
+
+    #[test]
+    fn provisions_are_parsed() {
+        let soname = Provision::parse("libfoo.so=1.2-64");
+        assert_eq!(
+            Provision::Soname(SonameProvision {
+                name: "libfoo.so".to_owned(),
+                version: Some("1.2".to_owned()),
+                bits: Some(64),
+            }),
+            soname
+        );
+        assert_eq!("libfoo.so=1.2-64", soname.to_string());
+        assert_eq!(
+            Provision::Package {
+                name: "java-runtime".to_owned(),
+                version: Some("17-1".to_owned()),
+            },
+            Provision::parse("java-runtime=17-1")
+        );
+        match Provision::parse("libbar.so") {
+            Provision::Soname(soname) => assert_eq!(None, soname.major()),
+            provision => panic!("{:?} is not soname", provision),
+        }
+    }
+}
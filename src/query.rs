@@ -0,0 +1,107 @@
+use crate::{Package, Repository};
+use std::sync::Arc;
+
+type Predicate<'a> = Box<dyn Fn(&Package) -> bool + 'a>;
+
+/// Iterator over repository packages with filters, see [`Repository::iter`]. Filters are
+/// combined with logical AND, packages are returned in database order
+pub struct PackageQuery<'a> {
+    packages: std::slice::Iter<'a, Arc<Package>>,
+    filters: Vec<Predicate<'a>>,
+}
+
+impl<'a> PackageQuery<'a> {
+    fn with<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(&Package) -> bool + 'a,
+    {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    /// Keep packages built for architecture, like `x86_64`. Packages for `any` are not kept
+    /// unless `any` is requested
+    pub fn filter_arch(self, arch: &str) -> Self {
+        let arch = arch.to_owned();
+        self.with(move |p| *p.architecture == *arch)
+    }
+
+    /// Keep packages which belong to group
+    pub fn with_group(self, group: &str) -> Self {
+        let group = group.to_owned();
+        self.with(move |p| p.groups().iter().any(|g| **g == *group))
+    }
+
+    /// Keep packages with license
+    pub fn with_license(self, license: &str) -> Self {
+        let license = license.to_owned();
+        self.with(move |p| p.licenses().iter().any(|l| **l == *license))
+    }
+
+    /// Keep packages which have run-time dependency on package name, regardless of version
+    /// constraints
+    pub fn depends_on(self, name: &str) -> Self {
+        let name = name.to_owned();
+        self.with(move |p| p.depends().iter().any(|d| *d.name == *name))
+    }
+
+    /// Keep packages which installed size is larger than `bytes`
+    pub fn larger_than(self, bytes: u64) -> Self {
+        self.with(move |p| p.installed_size > bytes)
+    }
+
+    /// Keep packages which installed size is smaller than `bytes`
+    pub fn smaller_than(self, bytes: u64) -> Self {
+        self.with(move |p| p.installed_size < bytes)
+    }
+
+    /// Keep packages matching custom predicate
+    pub fn matching<F>(self, predicate: F) -> Self
+    where
+        F: Fn(&Package) -> bool + 'a,
+    {
+        self.with(predicate)
+    }
+
+    /// Project matching packages to their names
+    pub fn names(self) -> impl Iterator<Item = &'a str> {
+        self.map(|p| p.name.as_str())
+    }
+}
+
+impl<'a> Iterator for PackageQuery<'a> {
+    type Item = &'a Package;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let filters = &self.filters;
+        self.packages
+            .by_ref()
+            .map(|p| &**p)
+            .find(|p| filters.iter().all(|f| f(p)))
+    }
+}
+
+impl Repository {
+    /// Iterate over packages, including base packages created for VCS packages. Returned
+    /// iterator has combinators for common filters
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::Repository;
+    ///
+    /// let repo = Repository::load("core", "https://geo.mirror.pkgbuild.com/core/os/x86_64").await?;
+    /// let large: Vec<&str> = repo
+    ///     .iter()
+    ///     .filter_arch("x86_64")
+    ///     .with_group("base-devel")
+    ///     .larger_than(10_000_000)
+    ///     .names()
+    ///     .collect();
+    /// ```
+    pub fn iter(&self) -> PackageQuery<'_> {
+        PackageQuery {
+            packages: self.inner.packages.iter(),
+            filters: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use crate::{BuildInfo, FilesMismatch, MtreeEntry, Package, PackageArchive, Repository};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::error::Error;
+
+/// Field which has different values in original package and its rebuild. Missing field is
+/// `None`, multi-value fields are joined with spaces
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct FieldDifference {
+    /// `.BUILDINFO` key, like `builddate`
+    pub field: String,
+    pub original: Option<String>,
+    pub rebuilt: Option<String>,
+}
+
+/// Differences between original package and its rebuild
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct RebuildDifferences {
+    /// package files have equal checksums, so rebuild is bit-for-bit reproducible
+    pub identical: bool,
+    /// `.BUILDINFO` fields with different values
+    pub buildinfo: Vec<FieldDifference>,
+    /// files present only in original package
+    pub only_in_original: Vec<String>,
+    /// files present only in rebuilt package
+    pub only_in_rebuilt: Vec<String>,
+    /// files present in both packages with different type, mode, link target or checksum
+    pub changed: Vec<String>,
+}
+
+impl PackageArchive {
+    /// Compare package with its rebuild. Both packages must have `.BUILDINFO` and `.MTREE`
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::PackageArchive;
+    ///
+    /// let original = PackageArchive::open("foo-1.0-1-x86_64.pkg.tar.zst")?;
+    /// let rebuilt = PackageArchive::open("rebuild/foo-1.0-1-x86_64.pkg.tar.zst")?;
+    /// for difference in original.compare_rebuild(&rebuilt)?.buildinfo {
+    ///     println!("{}: {:?} != {:?}", difference.field, difference.original, difference.rebuilt);
+    /// }
+    /// ```
+    pub fn compare_rebuild(
+        &self,
+        rebuilt: &PackageArchive,
+    ) -> Result<RebuildDifferences, Box<dyn Error>> {
+        let files = FilesMismatch::new(&self.file_list()?, &rebuilt.file_list()?);
+        Ok(RebuildDifferences {
+            identical: Sha256::digest(self.data()) == Sha256::digest(rebuilt.data()),
+            buildinfo: differences(
+                buildinfo_fields(&self.buildinfo()?),
+                buildinfo_fields(&rebuilt.buildinfo()?),
+            ),
+            only_in_original: files.missing_in_package,
+            only_in_rebuilt: files.missing_in_database,
+            changed: changed_files(&self.mtree()?, &rebuilt.mtree()?),
+        })
+    }
+}
+
+impl Repository {
+    /// Compare repository package by full name/base name or name with version with its
+    /// rebuild without downloading package. Checksum and metadata from database are compared
+    /// with rebuilt package, file lists are compared only if files metadata is loaded, see
+    /// [`Repository::load_files_metadata`]. Changed files are never reported. Fails with
+    /// [`LookupError`](crate::LookupError) if package cannot be found
+    pub fn compare_rebuild(
+        &self,
+        name: &str,
+        rebuilt: &PackageArchive,
+    ) -> Result<RebuildDifferences, Box<dyn Error>> {
+        let package = self.lookup(name)?;
+        let mut result = RebuildDifferences {
+            identical: format!("{:x}", Sha256::digest(rebuilt.data()))
+                == package.sha256_sum.to_lowercase(),
+            buildinfo: differences(
+                package_fields(package),
+                buildinfo_fields(&rebuilt.buildinfo()?)
+                    .into_iter()
+                    .filter(|(field, _)| PACKAGE_FIELDS.contains(&field.as_str()))
+                    .collect(),
+            ),
+            ..RebuildDifferences::default()
+        };
+        if let Some(files) = self.get_package_files(&package.name) {
+            let files = FilesMismatch::new(files, &rebuilt.file_list()?);
+            result.only_in_original = files.missing_in_package;
+            result.only_in_rebuilt = files.missing_in_database;
+        }
+        Ok(result)
+    }
+}
+
+/// `.BUILDINFO` fields which are also stored in repository database
+const PACKAGE_FIELDS: &[&str] = &[
+    "pkgname",
+    "pkgbase",
+    "pkgver",
+    "pkgarch",
+    "packager",
+    "builddate",
+];
+
+fn package_fields(package: &Package) -> Vec<(String, String)> {
+    let mut fields = vec![("pkgname", package.name.clone())];
+    if let Some(base) = &package.base {
+        fields.push(("pkgbase", base.clone()));
+    }
+    fields.push(("pkgver", package.version.clone()));
+    fields.push(("pkgarch", package.architecture.to_string()));
+    fields.push(("packager", package.packager.to_string()));
+    fields.push(("builddate", package.build_date.timestamp().to_string()));
+    fields
+        .into_iter()
+        .map(|(field, value)| (field.to_owned(), value))
+        .collect()
+}
+
+/// `.BUILDINFO` keys and values in file order
+fn buildinfo_fields(info: &BuildInfo) -> Vec<(String, String)> {
+    let optional = vec![
+        ("format", info.format.map(|f| f.to_string())),
+        ("pkgname", Some(info.name.clone())),
+        ("pkgbase", info.base.clone()),
+        ("pkgver", Some(info.version.clone())),
+        ("pkgarch", info.architecture.clone()),
+        ("pkgbuild_sha256sum", info.pkgbuild_sha256_sum.clone()),
+        ("packager", info.packager.clone()),
+        (
+            "builddate",
+            info.build_date.map(|d| d.timestamp().to_string()),
+        ),
+        ("builddir", info.build_dir.clone()),
+        ("startdir", info.start_dir.clone()),
+        ("buildtool", info.build_tool.clone()),
+        ("buildtoolver", info.build_tool_version.clone()),
+    ];
+    let installed: Vec<String> = info
+        .installed
+        .iter()
+        .map(|i| format!("{}-{}-{}", i.name, i.version, i.architecture))
+        .collect();
+    let multiple = vec![
+        ("buildenv", info.build_env.join(" ")),
+        ("options", info.options.join(" ")),
+        ("installed", installed.join(" ")),
+    ];
+    optional
+        .into_iter()
+        .filter_map(|(field, value)| Some((field.to_owned(), value?)))
+        .chain(
+            multiple
+                .into_iter()
+                .filter(|(_, value)| !value.is_empty())
+                .map(|(field, value)| (field.to_owned(), value)),
+        )
+        .chain(info.extra.iter().cloned())
+        .collect()
+}
+
+/// Fields with different values in original order, followed by fields present only in rebuild
+fn differences(
+    original: Vec<(String, String)>,
+    rebuilt: Vec<(String, String)>,
+) -> Vec<FieldDifference> {
+    let rebuilt_values: HashMap<&str, &str> = rebuilt
+        .iter()
+        .map(|(field, value)| (field.as_str(), value.as_str()))
+        .collect();
+    let original_fields: HashMap<&str, &str> = original
+        .iter()
+        .map(|(field, value)| (field.as_str(), value.as_str()))
+        .collect();
+    let mut result: Vec<FieldDifference> = original
+        .iter()
+        .filter(|(field, value)| rebuilt_values.get(field.as_str()) != Some(&value.as_str()))
+        .map(|(field, value)| FieldDifference {
+            field: field.clone(),
+            original: Some(value.clone()),
+            rebuilt: rebuilt_values.get(field.as_str()).map(|v| v.to_string()),
+        })
+        .collect();
+    result.extend(
+        rebuilt
+            .iter()
+            .filter(|(field, _)| !original_fields.contains_key(field.as_str()))
+            .map(|(field, value)| FieldDifference {
+                field: field.clone(),
+                original: None,
+                rebuilt: Some(value.clone()),
+            }),
+    );
+    result
+}
+
+/// Paths present in both mtrees with different contents or attributes, in original order.
+/// Modification times and owners are not compared
+fn changed_files(original: &[MtreeEntry], rebuilt: &[MtreeEntry]) -> Vec<String> {
+    let rebuilt: HashMap<&str, &MtreeEntry> =
+        rebuilt.iter().map(|e| (e.path.as_str(), e)).collect();
+    original
+        .iter()
+        .filter(|entry| !entry.path.starts_with('.'))
+        .filter(|entry| match rebuilt.get(entry.path.as_str()) {
+            Some(other) => {
+                entry.entry_type != other.entry_type
+                    || entry.mode != other.mode
+                    || entry.size != other.size
+                    || entry.sha256_sum != other.sha256_sum
+                    || entry.link != other.link
+            }
+            None => false,
+        })
+        .map(|entry| entry.path.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rebuild::differences;
+    use crate::FieldDifference;
+
+    #[test]
+    fn field_differences() {
+        let fields = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(f, v)| (f.to_string(), v.to_string()))
+                .collect()
+        };
+        let result = differences(
+            fields(&[
+                ("pkgname", "foo"),
+                ("builddate", "1"),
+                ("builddir", "/build"),
+            ]),
+            fields(&[
+                ("pkgname", "foo"),
+                ("builddate", "2"),
+                ("startdir", "/start"),
+            ]),
+        );
+        assert_eq!(
+            vec![
+                FieldDifference {
+                    field: "builddate".to_owned(),
+                    original: Some("1".to_owned()),
+                    rebuilt: Some("2".to_owned()),
+                },
+                FieldDifference {
+                    field: "builddir".to_owned(),
+                    original: Some("/build".to_owned()),
+                    rebuilt: None,
+                },
+                FieldDifference {
+                    field: "startdir".to_owned(),
+                    original: None,
+                    rebuilt: Some("/start".to_owned()),
+                },
+            ],
+            result
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use crate::footprint::table;
+use crate::{Inner, Package, Repository};
+use rustc_hash::FxHashMap;
+use std::sync::Arc;
+
+/// Reverse-dependency and provider indexes, see [`RepositoryBuilder::index_relations`]
+///
+/// [`RepositoryBuilder::index_relations`]: crate::RepositoryBuilder::index_relations
+#[derive(Default)]
+pub(crate) struct Relations {
+    /// packages by names of their run-time dependencies
+    dependents: FxHashMap<Arc<str>, Vec<Arc<Package>>>,
+    /// packages by names they provide
+    providers: FxHashMap<String, Vec<Arc<Package>>>,
+}
+
+impl Relations {
+    fn new(packages: &[Arc<Package>]) -> Self {
+        let mut relations = Relations::default();
+        for package in packages {
+            for dependency in package.depends.iter().flatten() {
+                let dependents = relations
+                    .dependents
+                    .entry(dependency.name.clone())
+                    .or_default();
+                // package may depend on the same name with several constraints
+                if !dependents.last().is_some_and(|p| Arc::ptr_eq(p, package)) {
+                    dependents.push(package.clone());
+                }
+            }
+            for provision in package.provides.iter().flatten() {
+                relations
+                    .providers
+                    .entry(provision_name(provision).to_owned())
+                    .or_default()
+                    .push(package.clone());
+            }
+        }
+        relations
+    }
+}
+
+impl Relations {
+    /// Approximate memory used by indexes, names are counted with string pool
+    pub(crate) fn memory_footprint(&self) -> usize {
+        let lists = |lists: &mut dyn Iterator<Item = &Vec<Arc<Package>>>| {
+            lists
+                .map(|l| l.capacity() * std::mem::size_of::<Arc<Package>>())
+                .sum::<usize>()
+        };
+        table::<Arc<str>, Vec<Arc<Package>>>(self.dependents.capacity())
+            + lists(&mut self.dependents.values())
+            + table::<String, Vec<Arc<Package>>>(self.providers.capacity())
+            + self.providers.keys().map(String::capacity).sum::<usize>()
+            + lists(&mut self.providers.values())
+    }
+}
+
+impl Inner {
+    /// Build relation indexes of loaded packages
+    pub(crate) fn index_relations(&self) {
+        let _ = self.relations.set(Relations::new(&self.packages));
+    }
+}
+
+/// Name part of `name=version` provision
+pub(crate) fn provision_name(provision: &str) -> &str {
+    provision.split('=').next().unwrap_or(provision)
+}
+
+impl Repository {
+    /// Packages which have `name` in their run-time dependencies, in repository order. Uses
+    /// index if it is built with [`RepositoryBuilder::index_relations`], otherwise all packages
+    /// are checked
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::RepositoryBuilder;
+    ///
+    /// let repo = RepositoryBuilder::new("mingw64", "http://repo.msys2.org/mingw/x86_64")
+    ///                 .index_relations(true)
+    ///                 .load()
+    ///                 .await?;
+    /// for package in repo.dependents("mingw-w64-x86_64-gtk3") {
+    ///     println!("{}", package.name);
+    /// }
+    /// ```
+    ///
+    /// [`RepositoryBuilder::index_relations`]: crate::RepositoryBuilder::index_relations
+    pub fn dependents(&self, name: &str) -> Vec<&Package> {
+        match self.inner.relations.get() {
+            Some(relations) => relations
+                .dependents
+                .get(name)
+                .map(|packages| packages.iter().map(|p| p as &Package).collect())
+                .unwrap_or_default(),
+            None => self
+                .into_iter()
+                .filter(|p| p.depends.iter().flatten().any(|d| &*d.name == name))
+                .collect(),
+        }
+    }
+
+    /// Packages which can satisfy dependency on `name`: package with this name, if it exists,
+    /// followed by packages which provide it. Uses index if it is built with
+    /// [`RepositoryBuilder::index_relations`], otherwise all packages are checked
+    ///
+    /// [`RepositoryBuilder::index_relations`]: crate::RepositoryBuilder::index_relations
+    pub fn providers(&self, name: &str) -> Vec<&Package> {
+        let providers: Vec<&Package> = match self.inner.relations.get() {
+            Some(relations) => relations
+                .providers
+                .get(name)
+                .map(|packages| packages.iter().map(|p| p as &Package).collect())
+                .unwrap_or_default(),
+            None => self
+                .into_iter()
+                .filter(|p| {
+                    p.provides
+                        .iter()
+                        .flatten()
+                        .any(|p| provision_name(p) == name)
+                })
+                .collect(),
+        };
+        self.get_package_by_name(name)
+            .into_iter()
+            .chain(providers.into_iter().filter(|p| p.name != name))
+            .collect()
+    }
+}
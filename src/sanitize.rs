@@ -0,0 +1,84 @@
+use crate::Package;
+use std::fmt::{Display, Formatter};
+use std::path::{Component, Path};
+
+/// Returned when file name or archive member path would escape destination directory
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct UnsafePathError {
+    path: String,
+}
+
+impl UnsafePathError {
+    fn new(path: &str) -> Self {
+        UnsafePathError {
+            path: path.to_owned(),
+        }
+    }
+
+    /// Rejected path
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+impl Display for UnsafePathError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unsafe path {}", self.path)
+    }
+}
+
+impl std::error::Error for UnsafePathError {}
+
+impl From<UnsafePathError> for std::io::Error {
+    fn from(e: UnsafePathError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+    }
+}
+
+/// Check that name is a plain file name: not empty, not `.` or `..` and without path
+/// separators or NUL bytes
+pub(crate) fn check_file_name(name: &str) -> Result<(), UnsafePathError> {
+    if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\', '\0']) {
+        return Err(UnsafePathError::new(name));
+    }
+    Ok(())
+}
+
+/// Check that archive member path is relative and has no `..` components
+pub(crate) fn check_member_path(path: &Path) -> Result<(), UnsafePathError> {
+    let safe = path.components().all(|component| match component {
+        Component::Normal(_) | Component::CurDir => true,
+        Component::ParentDir | Component::RootDir | Component::Prefix(_) => false,
+    });
+    if safe {
+        Ok(())
+    } else {
+        Err(UnsafePathError::new(&path.to_string_lossy()))
+    }
+}
+
+impl Package {
+    /// File name which can be safely joined to destination directory. Fails if file name
+    /// from database is a path, like `../../etc/passwd`
+    pub fn safe_file_name(&self) -> Result<&str, UnsafePathError> {
+        check_file_name(&self.file_name)?;
+        Ok(&self.file_name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sanitize::{check_file_name, check_member_path};
+    use std::path::Path;
+
+    #[test]
+    fn unsafe_paths_are_rejected() {
+        assert!(check_file_name("foo-1.0-1-any.pkg.tar.zst").is_ok());
+        for name in ["", "..", "../foo", "/etc/passwd", "dir\\foo"] {
+            assert!(check_file_name(name).is_err(), "{}", name);
+        }
+        assert!(check_member_path(Path::new("./usr/bin/foo")).is_ok());
+        assert!(check_member_path(Path::new("/etc/passwd")).is_err());
+        assert!(check_member_path(Path::new("usr/../../etc/passwd")).is_err());
+    }
+}
@@ -0,0 +1,166 @@
+use crate::{
+    vercmp, InstalledPackage, LocalDatabase, Package, Progress, Repository, RepositoryBuilder,
+};
+use futures::{StreamExt, TryStreamExt};
+use std::cmp::Ordering;
+use std::error::Error;
+use std::iter::FromIterator;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+/// Ordered collection of repositories. Like in `pacman.conf`, packages from repositories
+/// added earlier take priority
+///
+/// # Example
+/// ```ignore
+/// use archlinux_repo::{LocalDatabase, Repository, RepositorySet};
+///
+/// let mut repos = RepositorySet::new();
+/// repos.push(Repository::load("core", "https://geo.mirror.pkgbuild.com/core/os/x86_64").await?);
+/// repos.push(Repository::load("extra", "https://geo.mirror.pkgbuild.com/extra/os/x86_64").await?);
+/// let local = LocalDatabase::open(LocalDatabase::DEFAULT_PATH)?;
+/// for upgrade in repos.system_upgrades(&local) {
+///     println!("{} {} -> {}", &upgrade.installed.name, &upgrade.installed.version, &upgrade.available.version);
+/// }
+/// ```
+#[derive(Default)]
+pub struct RepositorySet {
+    repositories: Vec<Repository>,
+}
+
+/// Load progress of repository loaded by [`RepositorySet::load`]
+#[derive(Clone, Debug)]
+pub struct SetProgress<'a> {
+    /// name of repository which reported progress
+    pub repository: &'a str,
+    /// progress of repository
+    pub progress: Progress,
+    /// how many repositories are fully loaded
+    pub loaded: usize,
+    /// how many repositories are loaded
+    pub total: usize,
+}
+
+/// Installed package which has newer version in repository
+#[derive(Clone, Copy)]
+pub struct Upgrade<'a> {
+    /// installed package
+    pub installed: &'a InstalledPackage,
+    /// newer package
+    pub available: &'a Package,
+    /// repository of newer package
+    pub repository: &'a Repository,
+}
+
+impl RepositorySet {
+    /// Create empty set
+    pub fn new() -> Self {
+        RepositorySet::default()
+    }
+
+    /// Load repositories concurrently, at most `concurrency` at once. Repositories keep
+    /// builders order as priority. Progress of all repositories is reported to `progress`,
+    /// builder progress listeners are called too. Fails with first loading error
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::{RepositoryBuilder, RepositorySet};
+    ///
+    /// let builders = ["core", "extra", "multilib"]
+    ///     .iter()
+    ///     .map(|name| {
+    ///         RepositoryBuilder::new(name, "https://geo.mirror.pkgbuild.com/$repo/os/$arch")
+    ///             .arch("x86_64")
+    ///     })
+    ///     .collect();
+    /// let repos = RepositorySet::load(builders, 2, |p| {
+    ///     println!("[{}/{}] {}: {}", p.loaded, p.total, p.repository, p.progress)
+    /// })
+    /// .await?;
+    /// ```
+    pub async fn load<F>(
+        builders: Vec<RepositoryBuilder>,
+        concurrency: usize,
+        progress: F,
+    ) -> Result<RepositorySet, Box<dyn Error>>
+    where
+        F: Fn(SetProgress),
+    {
+        let total = builders.len();
+        let loaded = AtomicUsize::new(0);
+        let (progress, loaded) = (&progress, &loaded);
+        let repositories = futures::stream::iter(builders)
+            .map(|builder| async move {
+                let name = builder.name.clone();
+                let repository = Repository::new(builder, |p| {
+                    progress(SetProgress {
+                        repository: &name,
+                        progress: p,
+                        loaded: loaded.load(AtomicOrdering::Relaxed),
+                        total,
+                    })
+                })
+                .await?;
+                loaded.fetch_add(1, AtomicOrdering::Relaxed);
+                Ok::<_, Box<dyn Error>>(repository)
+            })
+            .buffered(concurrency.max(1))
+            .try_collect()
+            .await?;
+        Ok(RepositorySet { repositories })
+    }
+
+    /// Add repository with lowest priority
+    pub fn push(&mut self, repository: Repository) {
+        self.repositories.push(repository);
+    }
+
+    /// Repositories in priority order
+    pub fn repositories(&self) -> &[Repository] {
+        &self.repositories
+    }
+
+    /// Get package by full name from first repository which has it
+    pub fn get_package_by_name(&self, name: &str) -> Option<(&Repository, &Package)> {
+        self.repositories
+            .iter()
+            .find_map(|repo| repo.get_package_by_name(name).map(|p| (repo, p)))
+    }
+
+    /// Installed packages which have newer version in repositories, in local database order.
+    /// Like pacman, only first repository which has package is checked
+    pub fn system_upgrades<'a>(&'a self, local: &'a LocalDatabase) -> Vec<Upgrade<'a>> {
+        local
+            .packages()
+            .iter()
+            .filter_map(|installed| {
+                let (repository, available) = self.get_package_by_name(&installed.name)?;
+                if vercmp(&available.version, &installed.version) == Ordering::Greater {
+                    Some(Upgrade {
+                        installed,
+                        available,
+                        repository,
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Installed packages which no repository contains, like `pacman -Qm` lists
+    pub fn foreign_packages<'a>(&self, local: &'a LocalDatabase) -> Vec<&'a InstalledPackage> {
+        local
+            .packages()
+            .iter()
+            .filter(|installed| self.get_package_by_name(&installed.name).is_none())
+            .collect()
+    }
+}
+
+impl FromIterator<Repository> for RepositorySet {
+    fn from_iter<T: IntoIterator<Item = Repository>>(iter: T) -> Self {
+        RepositorySet {
+            repositories: iter.into_iter().collect(),
+        }
+    }
+}
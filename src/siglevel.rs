@@ -0,0 +1,205 @@
+use crate::{Package, Repository, RepositoryBuilder};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Whether signatures are checked, like pacman's `Never`, `Optional` and `Required` options
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum SignatureCheck {
+    /// signatures are not downloaded
+    Never,
+    /// signatures are downloaded if they exist
+    Optional,
+    /// missing signature is an error
+    Required,
+}
+
+/// Which keys are accepted, like pacman's `TrustedOnly` and `TrustAll` options
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum SignatureTrust {
+    /// only keys trusted by keyring owner
+    TrustedOnly,
+    /// any key from keyring, even if it is not trusted
+    TrustAll,
+}
+
+/// Signature policy of packages or database
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct SignaturePolicy {
+    pub check: SignatureCheck,
+    pub trust: SignatureTrust,
+}
+
+impl SignaturePolicy {
+    /// Apply `SigLevel` option without prefix. Returns `false` if option is unknown
+    fn apply(&mut self, option: &str) -> bool {
+        match option {
+            "Never" => self.check = SignatureCheck::Never,
+            "Optional" => self.check = SignatureCheck::Optional,
+            "Required" => self.check = SignatureCheck::Required,
+            "TrustedOnly" => self.trust = SignatureTrust::TrustedOnly,
+            "TrustAll" => self.trust = SignatureTrust::TrustAll,
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// Signature policy of repository, like pacman's `SigLevel`. This crate doesn't verify PGP
+/// signatures, it only enforces signature presence: database signature is downloaded unless
+/// its check is [`SignatureCheck::Never`], missing database or package signature is an error
+/// if check is [`SignatureCheck::Required`]. Trust is kept for the code which verifies
+/// signatures, like a `gpgv` wrapper
+///
+/// # Example
+/// ```
+/// use archlinux_repo::{SigLevel, SignatureCheck};
+///
+/// let level: SigLevel = "Required DatabaseOptional".parse().unwrap();
+/// assert_eq!(SignatureCheck::Required, level.package.check);
+/// assert_eq!(SignatureCheck::Optional, level.database.check);
+/// ```
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct SigLevel {
+    pub package: SignaturePolicy,
+    pub database: SignaturePolicy,
+}
+
+impl Default for SigLevel {
+    /// pacman default, `Required DatabaseOptional TrustedOnly`
+    fn default() -> Self {
+        SigLevel {
+            package: SignaturePolicy {
+                check: SignatureCheck::Required,
+                trust: SignatureTrust::TrustedOnly,
+            },
+            database: SignaturePolicy {
+                check: SignatureCheck::Optional,
+                trust: SignatureTrust::TrustedOnly,
+            },
+        }
+    }
+}
+
+/// Returned when `SigLevel` value has unknown option
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct SigLevelError {
+    option: String,
+}
+
+impl Display for SigLevelError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unknown SigLevel option {}", self.option)
+    }
+}
+
+impl std::error::Error for SigLevelError {}
+
+impl FromStr for SigLevel {
+    type Err = SigLevelError;
+
+    /// Parse `SigLevel` value from pacman.conf. Options are applied in order to
+    /// [`SigLevel::default`], options without `Package` or `Database` prefix apply to both
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut level = SigLevel::default();
+        for option in s.split_whitespace() {
+            let applied = if let Some(value) = option.strip_prefix("Package") {
+                level.package.apply(value)
+            } else if let Some(value) = option.strip_prefix("Database") {
+                level.database.apply(value)
+            } else {
+                level.package.apply(option) && level.database.apply(option)
+            };
+            if !applied {
+                return Err(SigLevelError {
+                    option: option.to_owned(),
+                });
+            }
+        }
+        Ok(level)
+    }
+}
+
+/// Returned when signature required by [`SigLevel`] is missing
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct MissingSignatureError {
+    file: String,
+}
+
+impl MissingSignatureError {
+    pub(crate) fn new(file: &str) -> Self {
+        MissingSignatureError {
+            file: file.to_owned(),
+        }
+    }
+
+    /// Url or name of file without signature
+    pub fn file(&self) -> &str {
+        &self.file
+    }
+}
+
+impl Display for MissingSignatureError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Required signature of {} is missing", self.file)
+    }
+}
+
+impl std::error::Error for MissingSignatureError {}
+
+impl RepositoryBuilder {
+    /// Set signature policy of repository, see [`SigLevel`]. Overrides
+    /// [`RepositoryBuilder::db_signature`]. Not set by default, so signatures are not required
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::RepositoryBuilder;
+    ///
+    /// let repo = RepositoryBuilder::new("core", "https://geo.mirror.pkgbuild.com/$repo/os/$arch")
+    ///                 .arch("x86_64")
+    ///                 .sig_level("Required DatabaseOptional".parse()?)
+    ///                 .load()
+    ///                 .await?;
+    /// ```
+    pub fn sig_level(mut self, level: SigLevel) -> Self {
+        self.options.sig_level = Some(level);
+        self
+    }
+}
+
+impl Repository {
+    /// Signature policy set by [`RepositoryBuilder::sig_level`]
+    pub fn sig_level(&self) -> Option<SigLevel> {
+        self.options.sig_level
+    }
+
+    /// Returns `true` if package signature must be checked, but database doesn't have it.
+    /// Detached signature may still exist on server
+    pub(crate) fn lacks_required_signature(&self, package: &Package) -> bool {
+        self.package_signature_check() == SignatureCheck::Required
+            && package.pgp_signature.is_empty()
+    }
+
+    /// Package signature check, [`SignatureCheck::Optional`] if policy is not set
+    pub(crate) fn package_signature_check(&self) -> SignatureCheck {
+        self.options
+            .sig_level
+            .map(|level| level.package.check)
+            .unwrap_or(SignatureCheck::Optional)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{SigLevel, SignatureCheck, SignatureTrust};
+
+    #[test]
+    fn parse_sig_level() {
+        let level: SigLevel = "Never PackageRequired DatabaseTrustAll".parse().unwrap();
+        assert_eq!(SignatureCheck::Required, level.package.check);
+        assert_eq!(SignatureTrust::TrustedOnly, level.package.trust);
+        assert_eq!(SignatureCheck::Never, level.database.check);
+        assert_eq!(SignatureTrust::TrustAll, level.database.trust);
+        assert_eq!(SigLevel::default(), "".parse().unwrap());
+        assert!("Required Sometimes".parse::<SigLevel>().is_err());
+    }
+}
@@ -0,0 +1,132 @@
+use crate::{Package, Repository};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Sub};
+
+/// Size in bytes, displayed like pacman does: `512 B`, `1.50 KiB`, `12.34 MiB`
+///
+/// # Example
+/// ```
+/// use archlinux_repo::ByteSize;
+///
+/// assert_eq!("512 B", ByteSize(512).to_string());
+/// assert_eq!("1.50 MiB", (ByteSize(1024 * 1024) + ByteSize(512 * 1024)).to_string());
+/// ```
+#[derive(
+    Serialize, Deserialize, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default,
+)]
+#[serde(transparent)]
+pub struct ByteSize(pub u64);
+
+impl ByteSize {
+    /// Size in bytes
+    pub fn bytes(self) -> u64 {
+        self.0
+    }
+}
+
+impl Display for ByteSize {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        const UNITS: [&str; 6] = ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+        if self.0 < 1024 {
+            return write!(f, "{} B", self.0);
+        }
+        let mut value = self.0 as f64 / 1024.0;
+        let mut unit = 0;
+        while value >= 1024.0 && unit < UNITS.len() - 1 {
+            value /= 1024.0;
+            unit += 1;
+        }
+        write!(f, "{:.2} {}", value, UNITS[unit])
+    }
+}
+
+impl From<u64> for ByteSize {
+    fn from(bytes: u64) -> Self {
+        ByteSize(bytes)
+    }
+}
+
+impl From<ByteSize> for u64 {
+    fn from(size: ByteSize) -> Self {
+        size.0
+    }
+}
+
+impl Add for ByteSize {
+    type Output = ByteSize;
+
+    fn add(self, rhs: ByteSize) -> Self::Output {
+        ByteSize(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign for ByteSize {
+    fn add_assign(&mut self, rhs: ByteSize) {
+        self.0 += rhs.0
+    }
+}
+
+/// Saturates at zero, so difference of sizes never panics
+impl Sub for ByteSize {
+    type Output = ByteSize;
+
+    fn sub(self, rhs: ByteSize) -> Self::Output {
+        ByteSize(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl Sum for ByteSize {
+    fn sum<I: Iterator<Item = ByteSize>>(iter: I) -> Self {
+        ByteSize(iter.map(|s| s.0).sum())
+    }
+}
+
+impl Package {
+    /// Package file size
+    pub fn compressed_size(&self) -> ByteSize {
+        ByteSize(self.compressed_size)
+    }
+
+    /// Installed files size
+    pub fn installed_size(&self) -> ByteSize {
+        ByteSize(self.installed_size)
+    }
+}
+
+impl Repository {
+    /// Total size of package files. Base packages created for VCS packages are not counted
+    pub fn total_compressed_size(&self) -> ByteSize {
+        self.real_packages().map(Package::compressed_size).sum()
+    }
+
+    /// Total installed size of packages. Base packages created for VCS packages are not
+    /// counted
+    pub fn total_installed_size(&self) -> ByteSize {
+        self.real_packages().map(Package::installed_size).sum()
+    }
+
+    fn real_packages(&self) -> impl Iterator<Item = &Package> {
+        self.inner
+            .packages
+            .iter()
+            .filter(move |p| !self.inner.synthetic.contains(&p.name))
+            .map(|p| &**p)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ByteSize;
+
+    #[test]
+    fn sizes_are_displayed_in_binary_units() {
+        assert_eq!("0 B", ByteSize(0).to_string());
+        assert_eq!("1023 B", ByteSize(1023).to_string());
+        assert_eq!("1.00 KiB", ByteSize(1024).to_string());
+        assert_eq!("9.54 MiB", ByteSize(10_000_000).to_string());
+        assert_eq!("16.00 EiB", ByteSize(u64::MAX).to_string());
+        assert_eq!(ByteSize(0), ByteSize(1) - ByteSize(2));
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{Package, Repository, RepositoryBuilder};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::error::Error;
+
+/// Owned copy of loaded repository which can be stored and turned back into [`Repository`]
+/// without downloading it
+///
+/// # Example
+/// ```ignore
+/// use archlinux_repo::{Repository, RepositorySnapshot};
+///
+/// let repo = Repository::load("mingw64", "http://repo.msys2.org/mingw/x86_64").await?;
+/// let json = serde_json::to_string(&repo.snapshot())?;
+/// let snapshot: RepositorySnapshot = serde_json::from_str(&json)?;
+/// let repo = Repository::from_snapshot(snapshot)?;
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RepositorySnapshot {
+    /// repository name
+    pub name: String,
+    /// repository url
+    pub url: String,
+    /// when repository was loaded
+    #[serde(with = "crate::data::date_serde")]
+    pub created: DateTime<Utc>,
+    /// packages from repository database. Base packages created for VCS packages are not
+    /// included, they are created again when snapshot is loaded
+    pub packages: Vec<Package>,
+    /// package files by package name, if files metadata was loaded
+    pub files: Option<HashMap<String, Vec<String>>>,
+    /// `Last-Modified` time of repository database, used by [`Repository::sync`]
+    #[serde(default, with = "crate::data::optional_date_serde")]
+    pub last_modified: Option<DateTime<Utc>>,
+    /// `ETag` of repository database, used by [`Repository::sync`]
+    #[serde(default)]
+    pub etag: Option<String>,
+}
+
+impl Repository {
+    /// Copy repository packages and files metadata into snapshot
+    pub fn snapshot(&self) -> RepositorySnapshot {
+        let files = self.inner.package_files.get().map(|files| {
+            files
+                .iter()
+                .map(|(name, files)| (name.to_owned(), files.files.clone()))
+                .collect()
+        });
+        RepositorySnapshot {
+            name: self.name.to_owned(),
+            url: self.url.to_owned(),
+            created: self.inner.loaded_at,
+            packages: self
+                .inner
+                .packages
+                .iter()
+                .filter(|p| !self.inner.synthetic.contains(&p.name))
+                .map(|p| p.as_ref().clone())
+                .collect(),
+            files,
+            last_modified: self.inner.last_modified,
+            etag: self.inner.etag.clone(),
+        }
+    }
+
+    /// Create repository from snapshot with default settings
+    pub fn from_snapshot(snapshot: RepositorySnapshot) -> Result<Repository, Box<dyn Error>> {
+        RepositoryBuilder::new(&snapshot.name, &snapshot.url).load_snapshot(snapshot)
+    }
+}
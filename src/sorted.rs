@@ -0,0 +1,89 @@
+use crate::{Inner, Package, Repository};
+use std::ops::{Bound, RangeBounds};
+use std::sync::Arc;
+
+impl Inner {
+    /// Packages sorted by name. Built on first use, repository is not changed after loading
+    fn sorted(&self) -> &[Arc<Package>] {
+        self.sorted.get_or_init(|| {
+            let mut sorted = self.packages.clone();
+            sorted.sort_by(|a, b| a.name.cmp(&b.name));
+            sorted
+        })
+    }
+}
+
+impl Repository {
+    /// Get up to `limit` packages starting from `offset` in order of package names.
+    /// Name index is built on first call
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::Repository;
+    ///
+    /// let repo = Repository::load("mingw64", "http://repo.msys2.org/mingw/x86_64").await?;
+    /// let second_page: Vec<_> = repo.page(50, 50).collect();
+    /// ```
+    pub fn page(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> impl ExactSizeIterator<Item = &Package> + '_ {
+        let sorted = self.inner.sorted();
+        let start = offset.min(sorted.len());
+        let end = start.saturating_add(limit).min(sorted.len());
+        sorted[start..end].iter().map(|p| p.as_ref())
+    }
+
+    /// Get packages which names are in `range`, in order of package names. Packages are
+    /// found with binary search over name index, which is built on first call
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::Repository;
+    ///
+    /// let repo = Repository::load("mingw64", "http://repo.msys2.org/mingw/x86_64").await?;
+    /// for package in repo.packages_in_range("mingw-w64-x86_64-a".."mingw-w64-x86_64-c") {
+    ///     println!("{}", package.name);
+    /// }
+    /// ```
+    pub fn packages_in_range<'a, R>(&self, range: R) -> impl ExactSizeIterator<Item = &Package> + '_
+    where
+        R: RangeBounds<&'a str>,
+    {
+        let sorted = self.inner.sorted();
+        let position = |bound: Bound<&&str>, include: bool| match bound {
+            Bound::Included(name) if include => sorted.partition_point(|p| p.name.as_str() < *name),
+            Bound::Included(name) => sorted.partition_point(|p| p.name.as_str() <= *name),
+            Bound::Excluded(name) if include => {
+                sorted.partition_point(|p| p.name.as_str() <= *name)
+            }
+            Bound::Excluded(name) => sorted.partition_point(|p| p.name.as_str() < *name),
+            Bound::Unbounded if include => 0,
+            Bound::Unbounded => sorted.len(),
+        };
+        let start = position(range.start_bound(), true);
+        let end = position(range.end_bound(), false).max(start);
+        sorted[start..end].iter().map(|p| p.as_ref())
+    }
+
+    /// Get packages which names start with `prefix`, in order of package names. Takes
+    /// `O(log n + k)` time using name index, which is built on first call
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::Repository;
+    ///
+    /// let repo = Repository::load("mingw64", "http://repo.msys2.org/mingw/x86_64").await?;
+    /// for package in repo.with_prefix("mingw-w64-x86_64-python-") {
+    ///     println!("{}", package.name);
+    /// }
+    /// ```
+    pub fn with_prefix(&self, prefix: &str) -> impl ExactSizeIterator<Item = &Package> + '_ {
+        let sorted = self.inner.sorted();
+        let start = sorted.partition_point(|p| p.name.as_str() < prefix);
+        // names with same prefix are next to each other in sorted list
+        let count = sorted[start..].partition_point(|p| p.name.starts_with(prefix));
+        sorted[start..start + count].iter().map(|p| p.as_ref())
+    }
+}
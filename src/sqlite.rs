@@ -0,0 +1,134 @@
+use crate::{Dependency, Repository};
+use rusqlite::{params, Connection, Transaction};
+use std::path::Path;
+
+const SCHEMA: &str = "
+CREATE TABLE packages (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    version TEXT NOT NULL,
+    base TEXT,
+    description TEXT,
+    file_name TEXT NOT NULL,
+    compressed_size INTEGER NOT NULL,
+    installed_size INTEGER NOT NULL,
+    md5_sum TEXT NOT NULL,
+    sha256_sum TEXT NOT NULL,
+    home_url TEXT,
+    architecture TEXT NOT NULL,
+    build_date INTEGER NOT NULL,
+    packager TEXT NOT NULL
+);
+CREATE TABLE dependencies (
+    package_id INTEGER NOT NULL REFERENCES packages(id),
+    kind TEXT NOT NULL,
+    name TEXT NOT NULL,
+    version_constraint TEXT,
+    version TEXT,
+    description TEXT
+);
+CREATE TABLE provides (
+    package_id INTEGER NOT NULL REFERENCES packages(id),
+    provision TEXT NOT NULL
+);
+CREATE TABLE files (
+    package_id INTEGER NOT NULL REFERENCES packages(id),
+    path TEXT NOT NULL
+);
+CREATE INDEX packages_name ON packages(name);
+CREATE INDEX dependencies_name ON dependencies(name);
+CREATE INDEX dependencies_package ON dependencies(package_id);
+CREATE INDEX provides_package ON provides(package_id);
+CREATE INDEX files_package ON files(package_id);
+CREATE INDEX files_path ON files(path);
+";
+
+impl Repository {
+    /// Write repository into new SQLite database file. Files are exported only if files
+    /// metadata is loaded. See [`Repository::export_sqlite`] for schema
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::Repository;
+    ///
+    /// let repo = Repository::load("mingw64", "http://repo.msys2.org/mingw/x86_64").await?;
+    /// repo.to_sqlite_file("mingw64.sqlite")?;
+    /// ```
+    pub fn to_sqlite_file<P: AsRef<Path>>(&self, path: P) -> rusqlite::Result<()> {
+        let mut connection = Connection::open(path)?;
+        self.export_sqlite(&mut connection)
+    }
+
+    /// Create tables in SQLite database and write repository into them in single transaction.
+    /// Tables are:
+    /// - `packages` - one row per package, `id` is referenced by other tables
+    /// - `dependencies` - `kind` is `depends`, `optdepends`, `makedepends` or `checkdepends`
+    /// - `provides` - raw provision strings
+    /// - `files` - file paths, filled only if files metadata is loaded
+    pub fn export_sqlite(&self, connection: &mut Connection) -> rusqlite::Result<()> {
+        let transaction = connection.transaction()?;
+        transaction.execute_batch(SCHEMA)?;
+        self.insert_rows(&transaction)?;
+        transaction.commit()
+    }
+
+    fn insert_rows(&self, transaction: &Transaction) -> rusqlite::Result<()> {
+        let mut insert_package = transaction.prepare(
+            "INSERT INTO packages (name, version, base, description, file_name, compressed_size,
+            installed_size, md5_sum, sha256_sum, home_url, architecture, build_date, packager)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+        )?;
+        let mut insert_dependency = transaction.prepare(
+            "INSERT INTO dependencies (package_id, kind, name, version_constraint, version,
+            description) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )?;
+        let mut insert_provision =
+            transaction.prepare("INSERT INTO provides (package_id, provision) VALUES (?1, ?2)")?;
+        let mut insert_file =
+            transaction.prepare("INSERT INTO files (package_id, path) VALUES (?1, ?2)")?;
+        for package in self {
+            insert_package.execute(params![
+                &package.name,
+                &package.version,
+                &package.base,
+                &package.description,
+                &package.file_name,
+                package.compressed_size as i64,
+                package.installed_size as i64,
+                &package.md5_sum,
+                &package.sha256_sum,
+                &package.home_url,
+                package.architecture.as_ref(),
+                package.build_date.timestamp(),
+                package.packager.as_ref(),
+            ])?;
+            let id = transaction.last_insert_rowid();
+            let dependencies: [(&str, &Option<Vec<Dependency>>); 4] = [
+                ("depends", &package.depends),
+                ("optdepends", &package.optdepends),
+                ("makedepends", &package.makedepends),
+                ("checkdepends", &package.checkdepends),
+            ];
+            for (kind, dependencies) in dependencies {
+                for dependency in dependencies.iter().flatten() {
+                    let version = dependency.version.as_ref();
+                    insert_dependency.execute(params![
+                        id,
+                        kind,
+                        dependency.name.as_ref(),
+                        version.map(|v| v.constraint.to_string()),
+                        version.map(|v| &v.version),
+                        &dependency.description,
+                    ])?;
+                }
+            }
+            for provision in package.provides.iter().flatten() {
+                insert_provision.execute(params![id, provision])?;
+            }
+            for path in self.get_package_files(&package.name).into_iter().flatten() {
+                insert_file.execute(params![id, path])?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{Package, Repository};
+use chrono::{DateTime, Utc};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Packages built by one packager, see [`Repository::packager_stats`]
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct PackagerStats {
+    /// packager, like `John Doe <john@example.com>`
+    pub packager: Arc<str>,
+    /// number of packages
+    pub packages: usize,
+    /// total compressed size of packages
+    pub compressed_size: u64,
+    /// total installed size of packages
+    pub installed_size: u64,
+    /// build date of most recently built package
+    pub latest_build_date: DateTime<Utc>,
+}
+
+impl Repository {
+    /// Get `n` packages with largest installed size, largest first
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::Repository;
+    ///
+    /// let repo = Repository::load("mingw64", "http://repo.msys2.org/mingw/x86_64").await?;
+    /// for package in repo.top_by_installed_size(10) {
+    ///     println!("{} {}", package.name, package.installed_size);
+    /// }
+    /// ```
+    pub fn top_by_installed_size(&self, n: usize) -> Vec<&Package> {
+        self.top_by(n, |p| p.installed_size)
+    }
+
+    /// Get `n` packages with largest compressed (download) size, largest first
+    pub fn top_by_compressed_size(&self, n: usize) -> Vec<&Package> {
+        self.top_by(n, |p| p.compressed_size)
+    }
+
+    fn top_by<F: Fn(&Package) -> u64>(&self, n: usize, size: F) -> Vec<&Package> {
+        let mut packages: Vec<&Package> = self.into_iter().collect();
+        // stable sort keeps database order of packages with same size
+        packages.sort_by_key(|p| std::cmp::Reverse(size(p)));
+        packages.truncate(n);
+        packages
+    }
+
+    /// Find packages which compression ratio (installed size to compressed size) differs from
+    /// repository median more than `factor` times, in either direction. Such packages usually
+    /// contain already compressed data or are unusually well compressible. Returns packages
+    /// with their ratios, most deviating first. Packages with zero sizes are skipped
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::Repository;
+    ///
+    /// let repo = Repository::load("mingw64", "http://repo.msys2.org/mingw/x86_64").await?;
+    /// for (package, ratio) in repo.compression_ratio_outliers(4.0) {
+    ///     println!("{} {:.1}", package.name, ratio);
+    /// }
+    /// ```
+    pub fn compression_ratio_outliers(&self, factor: f64) -> Vec<(&Package, f64)> {
+        let ratios: Vec<(&Package, f64)> = self
+            .into_iter()
+            .filter(|p| p.compressed_size > 0 && p.installed_size > 0)
+            .map(|p| (p, p.installed_size as f64 / p.compressed_size as f64))
+            .collect();
+        if ratios.is_empty() {
+            return Vec::new();
+        }
+        let mut sorted: Vec<f64> = ratios.iter().map(|(_, ratio)| *ratio).collect();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        let median = sorted[sorted.len() / 2];
+        // deviation from median in times, so 2x larger and 2x smaller ratios are equal
+        let deviation = |ratio: f64| (ratio / median).max(median / ratio);
+        let mut outliers: Vec<(&Package, f64)> = ratios
+            .into_iter()
+            .filter(|(_, ratio)| deviation(*ratio) > factor)
+            .collect();
+        outliers.sort_by(|a, b| {
+            deviation(b.1)
+                .partial_cmp(&deviation(a.1))
+                .unwrap_or(Ordering::Equal)
+        });
+        outliers
+    }
+
+    /// Group packages by packager. Result is sorted by number of packages, largest first.
+    /// Base packages created for VCS packages are not counted
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::Repository;
+    ///
+    /// let repo = Repository::load("mingw64", "http://repo.msys2.org/mingw/x86_64").await?;
+    /// for stats in repo.packager_stats() {
+    ///     println!("{}: {} packages", stats.packager, stats.packages);
+    /// }
+    /// ```
+    pub fn packager_stats(&self) -> Vec<PackagerStats> {
+        let mut stats: HashMap<&str, PackagerStats> = HashMap::new();
+        for package in self
+            .into_iter()
+            .filter(|p| !self.inner.synthetic.contains(&p.name))
+        {
+            let entry = stats
+                .entry(&package.packager)
+                .or_insert_with(|| PackagerStats {
+                    packager: package.packager.clone(),
+                    packages: 0,
+                    compressed_size: 0,
+                    installed_size: 0,
+                    latest_build_date: package.build_date,
+                });
+            entry.packages += 1;
+            entry.compressed_size += package.compressed_size;
+            entry.installed_size += package.installed_size;
+            entry.latest_build_date = entry.latest_build_date.max(package.build_date);
+        }
+        let mut stats: Vec<PackagerStats> = stats.into_values().collect();
+        stats.sort_by(|a, b| {
+            b.packages
+                .cmp(&a.packages)
+                .then_with(|| a.packager.cmp(&b.packager))
+        });
+        stats
+    }
+
+    /// Packages built in `from..to` time range, oldest first. Base packages created for VCS
+    /// packages are skipped
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::Repository;
+    /// use chrono::{Duration, Utc};
+    ///
+    /// let repo = Repository::load("mingw64", "http://repo.msys2.org/mingw/x86_64").await?;
+    /// let now = Utc::now();
+    /// for package in repo.packages_built_between(now - Duration::weeks(1), now) {
+    ///     println!("{} {}", package.name, package.build_date);
+    /// }
+    /// ```
+    pub fn packages_built_between(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Vec<&Package> {
+        let mut packages: Vec<&Package> = self
+            .into_iter()
+            .filter(|p| !self.inner.synthetic.contains(&p.name))
+            .filter(|p| from <= p.build_date && p.build_date < to)
+            .collect();
+        packages.sort_by_key(|p| p.build_date);
+        packages
+    }
+
+    /// Build date of most recently built package, `None` if repository is empty. Mirror which
+    /// has no new builds for a long time is likely stale
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::Repository;
+    /// use chrono::{Duration, Utc};
+    ///
+    /// let repo = Repository::load("mingw64", "http://repo.msys2.org/mingw/x86_64").await?;
+    /// if repo.newest_build_date().map_or(true, |date| Utc::now() - date > Duration::days(7)) {
+    ///     println!("Mirror is stale");
+    /// }
+    /// ```
+    pub fn newest_build_date(&self) -> Option<DateTime<Utc>> {
+        self.into_iter()
+            .filter(|p| !self.inner.synthetic.contains(&p.name))
+            .map(|p| p.build_date)
+            .max()
+    }
+}
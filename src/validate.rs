@@ -0,0 +1,210 @@
+use crate::sanitize::check_file_name;
+use crate::{Inner, Package, Repository};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use std::fmt::{Display, Formatter};
+
+/// Violated invariant of package entry, see [`Repository::validate`]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Violation {
+    /// checksum has wrong length or is not hexadecimal
+    InvalidDigest {
+        /// `MD5SUM` or `SHA256SUM`
+        field: &'static str,
+        value: String,
+    },
+    /// package file size is zero
+    ZeroSize,
+    /// build date is before first pacman release or after repository was loaded
+    ImplausibleBuildDate(DateTime<Utc>),
+    /// file name doesn't start with `name-version-arch.pkg.tar`
+    FileNameMismatch {
+        /// expected file name prefix
+        expected: String,
+        actual: String,
+    },
+    /// file name is a path which would escape download directory
+    UnsafeFileName(String),
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Violation::InvalidDigest { field, value } => write!(f, "invalid {} {}", field, value),
+            Violation::ZeroSize => write!(f, "package file size is zero"),
+            Violation::ImplausibleBuildDate(date) => write!(f, "implausible build date {}", date),
+            Violation::FileNameMismatch { expected, actual } => {
+                write!(f, "file name {} doesn't start with {}", actual, expected)
+            }
+            Violation::UnsafeFileName(name) => write!(f, "unsafe file name {}", name),
+        }
+    }
+}
+
+/// All violations of one package
+#[derive(Clone, Debug, PartialEq)]
+pub struct PackageViolations {
+    /// `name-version` of package
+    pub package: String,
+    pub violations: Vec<Violation>,
+}
+
+/// Returned when repository is loaded in strict mode and some packages violate invariants,
+/// see [`RepositoryBuilder::strict_validation`]
+///
+/// [`RepositoryBuilder::strict_validation`]: crate::RepositoryBuilder::strict_validation
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidationError {
+    packages: Vec<PackageViolations>,
+}
+
+impl ValidationError {
+    /// Packages which violate invariants
+    pub fn packages(&self) -> &[PackageViolations] {
+        &self.packages
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} packages failed validation", self.packages.len())?;
+        if let Some(first) = self.packages.first() {
+            write!(f, ", {}: {}", first.package, first.violations[0])?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+fn is_hex(value: &str, length: usize) -> bool {
+    value.len() == length && value.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+pub(crate) fn package_violations(package: &Package, loaded_at: DateTime<Utc>) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    for (field, value, length) in [
+        ("MD5SUM", &package.md5_sum, 32),
+        ("SHA256SUM", &package.sha256_sum, 64),
+    ] {
+        if !is_hex(value, length) {
+            violations.push(Violation::InvalidDigest {
+                field,
+                value: value.clone(),
+            });
+        }
+    }
+    // installed size of metapackages is zero, so only package file size is checked
+    if package.compressed_size == 0 {
+        violations.push(Violation::ZeroSize);
+    }
+    // pacman 1.0 was released in 2002, clocks of build machines may be slightly ahead
+    let earliest = Utc.with_ymd_and_hms(2002, 1, 1, 0, 0, 0).unwrap();
+    if package.build_date < earliest || package.build_date > loaded_at + Duration::days(1) {
+        violations.push(Violation::ImplausibleBuildDate(package.build_date));
+    }
+    let expected = format!(
+        "{}-{}-{}.pkg.tar",
+        package.name, package.version, package.architecture
+    );
+    if check_file_name(&package.file_name).is_err() {
+        violations.push(Violation::UnsafeFileName(package.file_name.clone()));
+    }
+    if !package.file_name.starts_with(&expected) {
+        violations.push(Violation::FileNameMismatch {
+            expected,
+            actual: package.file_name.clone(),
+        });
+    }
+    violations
+}
+
+impl Inner {
+    pub(crate) fn violations(&self) -> Vec<PackageViolations> {
+        self.packages
+            .iter()
+            .filter(|p| !self.synthetic.contains(&p.name))
+            .filter_map(|package| {
+                let violations = package_violations(package, self.loaded_at);
+                if violations.is_empty() {
+                    None
+                } else {
+                    Some(PackageViolations {
+                        package: format!("{}-{}", package.name, package.version),
+                        violations,
+                    })
+                }
+            })
+            .collect()
+    }
+
+    /// Fail if any package violates invariants
+    pub(crate) fn check_violations(&self) -> Result<(), ValidationError> {
+        let packages = self.violations();
+        if packages.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError { packages })
+        }
+    }
+}
+
+impl Repository {
+    /// Check invariants of package entries: checksum lengths, non-zero package size, plausible
+    /// build date and file name matching `name-version-arch` pattern. Returns packages which
+    /// violate them. Base packages created for VCS packages are not checked
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::Repository;
+    ///
+    /// let repo = Repository::load("mingw64", "http://repo.msys2.org/mingw/x86_64").await?;
+    /// for package in repo.validate() {
+    ///     for violation in package.violations {
+    ///         println!("{}: {}", package.package, violation);
+    ///     }
+    /// }
+    /// ```
+    pub fn validate(&self) -> Vec<PackageViolations> {
+        self.inner.violations()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::validate::{package_violations, Violation};
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn violations_are_found() {
+        let mut package: crate::Package = archlinux_repo_parser::from_str(
+            "%FILENAME%\nfoo-1.0-1-x86_64.pkg.tar.zst\n\n%NAME%\nfoo\n\n%VERSION%\n1.0-1\n\n\
+            %CSIZE%\n1024\n\n%ISIZE%\n0\n\n%MD5SUM%\nd41d8cd98f00b204e9800998ecf8427e\n\n\
+            %SHA256SUM%\ne3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855\n\n\
+            %PGPSIG%\nc2lnbmF0dXJl\n\n%ARCH%\nx86_64\n\n%BUILDDATE%\n1560520506\n\n\
+            %PACKAGER%\nTest Packager <test@example.com>\n\n",
+        )
+        .unwrap();
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        assert!(package_violations(&package, now).is_empty());
+
+        package.md5_sum = "d41d8cd98f00b204".to_owned();
+        package.compressed_size = 0;
+        package.build_date = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap();
+        package.file_name = "foo-1.0-1-any.pkg.tar.zst".to_owned();
+        assert_eq!(
+            vec![
+                Violation::InvalidDigest {
+                    field: "MD5SUM",
+                    value: "d41d8cd98f00b204".to_owned()
+                },
+                Violation::ZeroSize,
+                Violation::ImplausibleBuildDate(package.build_date),
+                Violation::FileNameMismatch {
+                    expected: "foo-1.0-1-x86_64.pkg.tar".to_owned(),
+                    actual: "foo-1.0-1-any.pkg.tar.zst".to_owned()
+                },
+            ],
+            package_violations(&package, now)
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use crate::{HttpError, Package, PackageArchive, Repository};
+use futures::StreamExt;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::error::Error;
+
+/// Difference between package files listed in files database and files in package archive
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct FilesMismatch {
+    /// files listed in database, but absent in package
+    pub missing_in_package: Vec<String>,
+    /// files present in package, but not listed in database
+    pub missing_in_database: Vec<String>,
+}
+
+impl FilesMismatch {
+    /// Compare file lists. Paths keep the order of their source list
+    pub fn new(database: &[String], package: &[String]) -> Self {
+        let database_set: HashSet<&str> = database.iter().map(String::as_str).collect();
+        let package_set: HashSet<&str> = package.iter().map(String::as_str).collect();
+        FilesMismatch {
+            missing_in_package: database
+                .iter()
+                .filter(|path| !package_set.contains(path.as_str()))
+                .cloned()
+                .collect(),
+            missing_in_database: package
+                .iter()
+                .filter(|path| !database_set.contains(path.as_str()))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Returns `true` if both lists have the same files
+    pub fn is_empty(&self) -> bool {
+        self.missing_in_package.is_empty() && self.missing_in_database.is_empty()
+    }
+}
+
+/// Settings of [`Repository::verify_repo`]
+#[derive(Clone, Debug)]
+pub struct VerifyOptions {
+    concurrency: usize,
+    checksum_sample: usize,
+}
+
+impl Default for VerifyOptions {
+    fn default() -> Self {
+        VerifyOptions {
+            concurrency: 8,
+            checksum_sample: 0,
+        }
+    }
+}
+
+impl VerifyOptions {
+    /// Create default options: 8 concurrent requests, no checksum checks
+    pub fn new() -> Self {
+        VerifyOptions::default()
+    }
+
+    /// Set how many requests can be sent at once
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Download `count` packages evenly spread over repository and check their SHA256
+    /// checksums. `0` disables checksum checks, `usize::MAX` checks every package
+    pub fn checksum_sample(mut self, count: usize) -> Self {
+        self.checksum_sample = count;
+        self
+    }
+}
+
+/// Problem with package file found by [`Repository::verify_repo`]
+#[derive(Serialize, Clone, Eq, PartialEq, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum VerifyProblem {
+    /// Server returned non-successful status
+    Missing { package: String, status: u16 },
+    /// `Content-Length` differs from package compressed size
+    SizeMismatch {
+        package: String,
+        expected: u64,
+        actual: Option<u64>,
+    },
+    /// Downloaded file has different checksum
+    ChecksumMismatch {
+        package: String,
+        expected: String,
+        actual: String,
+    },
+    /// Request failed
+    RequestFailed { package: String, error: String },
+    /// Signature is required by [`SigLevel`](crate::SigLevel), but neither database nor
+    /// server has it
+    MissingSignature { package: String },
+}
+
+/// Result of [`Repository::verify_repo`]. Can be serialized to get machine-readable report
+#[derive(Serialize, Clone, Eq, PartialEq, Debug, Default)]
+pub struct VerifyReport {
+    /// how many package files were checked
+    pub checked: usize,
+    /// how many package checksums were checked
+    pub checksums_checked: usize,
+    pub problems: Vec<VerifyProblem>,
+}
+
+impl VerifyReport {
+    /// Returns `true` if no problems were found
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+impl Repository {
+    /// Compare files database entry of package with contents of its archive.
+    /// Files metadata should be loaded, otherwise error is returned
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::{PackageArchive, RepositoryBuilder};
+    ///
+    /// let repo = RepositoryBuilder::new("mingw64", "http://repo.msys2.org/mingw/x86_64")
+    ///                 .files_metadata(true)
+    ///                 .load()
+    ///                 .await?;
+    /// let archive = PackageArchive::open("mingw-w64-x86_64-gtk3-3.24.9-4-any.pkg.tar.xz")?;
+    /// let mismatch = repo.verify_package_files("mingw-w64-x86_64-gtk3", &archive)?;
+    /// assert!(mismatch.is_empty());
+    /// ```
+    pub fn verify_package_files(
+        &self,
+        name: &str,
+        archive: &PackageArchive,
+    ) -> Result<FilesMismatch, Box<dyn Error>> {
+        let database = self
+            .get_package_files(name)
+            .ok_or_else(|| format!("Files metadata of package {} not found", name))?;
+        Ok(FilesMismatch::new(database, &archive.file_list()?))
+    }
+
+    /// Download package (or take it from package cache) and compare its contents with files
+    /// database entry. Files metadata is loaded if it wasn't. Fails with
+    /// [`LookupError`](crate::LookupError) if package cannot be found
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::Repository;
+    ///
+    /// let repo = Repository::load("mingw64", "http://repo.msys2.org/mingw/x86_64").await?;
+    /// let mismatch = repo.verify_files("mingw-w64-x86_64-gtk3").await?;
+    /// for file in mismatch.missing_in_database {
+    ///     println!("{} is not listed in files database", file);
+    /// }
+    /// ```
+    pub async fn verify_files(&self, name: &str) -> Result<FilesMismatch, Box<dyn Error>> {
+        let package = self.lookup(name)?;
+        self.load_files_metadata().await?;
+        let package_name = package.name.clone();
+        let response = self.request_package(name).await?;
+        if !response.is_success() {
+            let url = self.package_url(package);
+            let url = self.options.public_url(&url);
+            return Err(Box::new(
+                HttpError::from_response("GET", &url, response).await,
+            ));
+        }
+        let archive = PackageArchive::new(response.bytes().await?);
+        self.verify_package_files(&package_name, &archive)
+    }
+
+    /// Check that every package file exists on server and its size matches compressed size
+    /// from database. Files are checked with HEAD requests, packages selected by
+    /// [`VerifyOptions::checksum_sample`] are downloaded to check their checksums. If
+    /// signatures are required by [`SigLevel`](crate::SigLevel), packages without signature in
+    /// database must have detached signature on server.
+    /// Package cache directories are not used
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::{Repository, VerifyOptions};
+    ///
+    /// let repo = Repository::load("mingw64", "http://repo.msys2.org/mingw/x86_64").await?;
+    /// let report = repo.verify_repo(VerifyOptions::new().checksum_sample(10)).await;
+    /// println!("{}", serde_json::to_string(&report)?);
+    /// ```
+    pub async fn verify_repo(&self, options: VerifyOptions) -> VerifyReport {
+        let packages: Vec<&Package> = self.into_iter().collect();
+        let step = match options.checksum_sample {
+            0 => usize::MAX,
+            count => (packages.len() / count).max(1),
+        };
+        let checks = packages.iter().enumerate().map(|(i, package)| {
+            let checksum = i % step == 0 && i / step < options.checksum_sample;
+            self.verify_package(package, checksum)
+        });
+        let results: Vec<(Option<VerifyProblem>, bool)> = futures::stream::iter(checks)
+            .buffered(options.concurrency)
+            .collect()
+            .await;
+        let mut report = VerifyReport {
+            checked: results.len(),
+            ..VerifyReport::default()
+        };
+        for (problem, checksum_checked) in results {
+            if checksum_checked {
+                report.checksums_checked += 1;
+            }
+            report.problems.extend(problem);
+        }
+        report
+    }
+
+    /// Returns found problem and `true` if checksum was checked
+    async fn verify_package(
+        &self,
+        package: &Package,
+        checksum: bool,
+    ) -> (Option<VerifyProblem>, bool) {
+        let id = format!("{}-{}", package.name, package.version);
+        let url = self.package_url(package);
+        let response = if checksum {
+            self.http_client.get(&url).await
+        } else {
+            self.http_client.head(&url).await
+        };
+        let mut response = match response {
+            Ok(response) => response,
+            Err(e) => {
+                let problem = VerifyProblem::RequestFailed {
+                    package: id,
+                    error: self.options.redact_error(&url, e).to_string(),
+                };
+                return (Some(problem), false);
+            }
+        };
+        if !response.is_success() {
+            let problem = VerifyProblem::Missing {
+                package: id,
+                status: response.status(),
+            };
+            return (Some(problem), false);
+        }
+        if response.content_length() != Some(package.compressed_size) {
+            let problem = VerifyProblem::SizeMismatch {
+                package: id,
+                expected: package.compressed_size,
+                actual: response.content_length(),
+            };
+            return (Some(problem), false);
+        }
+        if self.lacks_required_signature(package) {
+            let signature_url = format!("{}.sig", url);
+            let found = matches!(
+                self.http_client.head(&signature_url).await,
+                Ok(response) if response.is_success()
+            );
+            if !found {
+                return (Some(VerifyProblem::MissingSignature { package: id }), false);
+            }
+        }
+        if !checksum {
+            return (None, false);
+        }
+        let mut hasher = Sha256::new();
+        loop {
+            match response.chunk().await {
+                Ok(Some(chunk)) => hasher.update(&chunk),
+                Ok(None) => break,
+                Err(e) => {
+                    let problem = VerifyProblem::RequestFailed {
+                        package: id,
+                        error: e.to_string(),
+                    };
+                    return (Some(problem), false);
+                }
+            }
+        }
+        let actual = format!("{:x}", hasher.finalize());
+        if actual != package.sha256_sum.to_lowercase() {
+            let problem = VerifyProblem::ChecksumMismatch {
+                package: id,
+                expected: package.sha256_sum.clone(),
+                actual,
+            };
+            return (Some(problem), true);
+        }
+        (None, true)
+    }
+}
@@ -0,0 +1,297 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
+
+/// Package version which can be sorted or used as key of maps and sets. Versions are ordered
+/// like [`vercmp`] does, so `1.01` equals `1.1`, but version without release is older than
+/// the same version with any release to keep ordering total. Use
+/// [`DependencyVersion::is_satisfied_by`](crate::DependencyVersion::is_satisfied_by) to
+/// check constraints, where missing release matches any release
+///
+/// # Example
+/// ```
+/// use archlinux_repo::Version;
+///
+/// let mut versions: Vec<Version> = vec!["1.10-1".into(), "1:0.1-1".into(), "1.9-1".into()];
+/// versions.sort();
+/// let sorted: Vec<&str> = versions.iter().map(Version::as_str).collect();
+/// assert_eq!(vec!["1.9-1", "1.10-1", "1:0.1-1"], sorted);
+/// assert_eq!(Version::from("1.01"), Version::from("1.1"));
+/// assert!(Version::from("1.0") < Version::from("1.0-1"));
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(transparent)]
+pub struct Version(String);
+
+impl Version {
+    /// Version string as it was given
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for Version {
+    fn from(version: &str) -> Self {
+        Version(version.to_owned())
+    }
+}
+
+impl From<String> for Version {
+    fn from(version: String) -> Self {
+        Version(version)
+    }
+}
+
+impl AsRef<str> for Version {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        total_cmp(&self.0, &other.0)
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+impl Hash for Version {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_version(&self.0, state)
+    }
+}
+
+/// Ordering of [`Version`]: like [`vercmp`], but missing release is older than any release
+pub(crate) fn total_cmp(a: &str, b: &str) -> Ordering {
+    let (epoch_a, version_a, release_a) = split_version(a);
+    let (epoch_b, version_b, release_b) = split_version(b);
+    rpmvercmp(epoch_a, epoch_b)
+        .then_with(|| rpmvercmp(version_a, version_b))
+        .then_with(|| match (release_a, release_b) {
+            (Some(a), Some(b)) => rpmvercmp(a, b),
+            (a, b) => a.is_some().cmp(&b.is_some()),
+        })
+}
+
+/// Hash version so that equal [`Version`]s have same hash. Segments of epoch, pkgver and
+/// release are hashed without separators and leading zeros
+pub(crate) fn hash_version<H: Hasher>(version: &str, state: &mut H) {
+    let (epoch, version, release) = split_version(version);
+    release.is_some().hash(state);
+    for part in [epoch, version, release.unwrap_or_default()] {
+        let mut segments = 0usize;
+        for run in part.split(|c: char| !c.is_ascii_alphanumeric()) {
+            let mut rest = run;
+            while !rest.is_empty() {
+                let numeric = rest.as_bytes()[0].is_ascii_digit();
+                let end = rest
+                    .find(|c: char| c.is_ascii_digit() != numeric)
+                    .unwrap_or(rest.len());
+                let (segment, tail) = rest.split_at(end);
+                let segment = match segment.trim_start_matches('0') {
+                    "" if numeric => "0",
+                    trimmed if numeric => trimmed,
+                    _ => segment,
+                };
+                segment.hash(state);
+                segments += 1;
+                rest = tail;
+            }
+        }
+        segments.hash(state);
+    }
+}
+
+/// Compare package versions like pacman's `vercmp` does. Versions have
+/// `[epoch:]pkgver[-pkgrel]` format, missing epoch is `0` and release is compared only if
+/// both versions have it
+///
+/// # Example
+/// ```
+/// use archlinux_repo::vercmp;
+/// use std::cmp::Ordering;
+///
+/// assert_eq!(Ordering::Less, vercmp("1.0-1", "1.0.1-1"));
+/// assert_eq!(Ordering::Greater, vercmp("1:1.0-1", "2.0-1"));
+/// ```
+pub fn vercmp(a: &str, b: &str) -> Ordering {
+    if a == b {
+        return Ordering::Equal;
+    }
+    let (epoch_a, version_a, release_a) = split_version(a);
+    let (epoch_b, version_b, release_b) = split_version(b);
+    rpmvercmp(epoch_a, epoch_b)
+        .then_with(|| rpmvercmp(version_a, version_b))
+        .then_with(|| match (release_a, release_b) {
+            (Some(a), Some(b)) => rpmvercmp(a, b),
+            _ => Ordering::Equal,
+        })
+}
+
+/// Split version into epoch, version and release
+fn split_version(version: &str) -> (&str, &str, Option<&str>) {
+    let (epoch, rest) = match version.find(|c: char| !c.is_ascii_digit()) {
+        Some(pos) if version[pos..].starts_with(':') => (&version[..pos], &version[pos + 1..]),
+        _ => ("0", version),
+    };
+    let epoch = if epoch.is_empty() { "0" } else { epoch };
+    match rest.rfind('-') {
+        Some(pos) => (epoch, &rest[..pos], Some(&rest[pos + 1..])),
+        None => (epoch, rest, None),
+    }
+}
+
+/// Segment-wise comparison used by rpm and pacman
+fn rpmvercmp(a: &str, b: &str) -> Ordering {
+    if a == b {
+        return Ordering::Equal;
+    }
+    let a = a.as_bytes();
+    let b = b.as_bytes();
+    let (mut one, mut two) = (0, 0);
+    while one < a.len() && two < b.len() {
+        let separator_start = (one, two);
+        while one < a.len() && !a[one].is_ascii_alphanumeric() {
+            one += 1;
+        }
+        while two < b.len() && !b[two].is_ascii_alphanumeric() {
+            two += 1;
+        }
+        if one == a.len() || two == b.len() {
+            break;
+        }
+        // segments are separated by different amount of characters
+        let separators = (one - separator_start.0, two - separator_start.1);
+        if separators.0 != separators.1 {
+            return separators.0.cmp(&separators.1);
+        }
+
+        let numeric = a[one].is_ascii_digit();
+        let segment_end = |s: &[u8], start: usize| {
+            let mut end = start;
+            while end < s.len()
+                && if numeric {
+                    s[end].is_ascii_digit()
+                } else {
+                    s[end].is_ascii_alphabetic()
+                }
+            {
+                end += 1;
+            }
+            end
+        };
+        let (end_one, end_two) = (segment_end(a, one), segment_end(b, two));
+        if end_two == two {
+            // numeric segment is always newer than alpha one
+            return if numeric {
+                Ordering::Greater
+            } else {
+                Ordering::Less
+            };
+        }
+        let (mut segment_one, mut segment_two) = (&a[one..end_one], &b[two..end_two]);
+        if numeric {
+            while segment_one.len() > 1 && segment_one[0] == b'0' {
+                segment_one = &segment_one[1..];
+            }
+            while segment_two.len() > 1 && segment_two[0] == b'0' {
+                segment_two = &segment_two[1..];
+            }
+            let length = segment_one.len().cmp(&segment_two.len());
+            if length != Ordering::Equal {
+                return length;
+            }
+        }
+        let ordering = segment_one.cmp(segment_two);
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+        one = end_one;
+        two = end_two;
+    }
+    if one == a.len() && two == b.len() {
+        return Ordering::Equal;
+    }
+    // remaining alpha segment never beats empty string
+    if (one == a.len() && !b[two].is_ascii_alphabetic())
+        || (one < a.len() && a[one].is_ascii_alphabetic())
+    {
+        Ordering::Less
+    } else {
+        Ordering::Greater
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{vercmp, Version};
+    use std::cmp::Ordering;
+    use std::collections::HashSet;
+
+    #[test]
+    fn equal_versions_have_same_hash() {
+        let versions: HashSet<Version> = ["1.01-1", "1.1-1", "1.1-01", "0:1.1-1", "1.1", "1.2-1"]
+            .iter()
+            .map(|v| Version::from(*v))
+            .collect();
+        assert_eq!(3, versions.len());
+        assert!(Version::from("1.0alpha") < Version::from("1.0"));
+    }
+
+    #[test]
+    fn versions_are_totally_ordered() {
+        let mut versions: Vec<Version> = ["1.0-2", "1.0", "1.0-1", "0.9", "1:0.1"]
+            .iter()
+            .map(|v| Version::from(*v))
+            .collect();
+        versions.sort();
+        let sorted: Vec<&str> = versions.iter().map(Version::as_str).collect();
+        assert_eq!(vec!["0.9", "1.0", "1.0-1", "1.0-2", "1:0.1"], sorted);
+        assert_ne!(Version::from("1.0"), Version::from("1.0-1"));
+    }
+
+    #[test]
+    fn compare_versions() {
+        assert_eq!(Ordering::Less, vercmp("1.0", "1.1"));
+        assert_eq!(Ordering::Less, vercmp("1.0", "1.0.1"));
+        assert_eq!(Ordering::Greater, vercmp("1.10", "1.9"));
+        assert_eq!(Ordering::Equal, vercmp("1.01", "1.1"));
+        assert_eq!(Ordering::Less, vercmp("1.0alpha", "1.0"));
+        assert_eq!(Ordering::Less, vercmp("1.0a", "1.0b"));
+        assert_eq!(Ordering::Greater, vercmp("1.0.a", "1.0alpha"));
+    }
+
+    #[test]
+    fn compare_releases() {
+        assert_eq!(Ordering::Less, vercmp("1.0-1", "1.0-2"));
+        assert_eq!(Ordering::Equal, vercmp("1.0-1", "1.0"));
+        assert_eq!(Ordering::Greater, vercmp("1.0.1-1", "1.0-9"));
+    }
+
+    #[test]
+    fn compare_epochs() {
+        assert_eq!(Ordering::Greater, vercmp("1:1.0-1", "2.0-1"));
+        assert_eq!(Ordering::Equal, vercmp("0:1.0-1", "1.0-1"));
+        assert_eq!(Ordering::Less, vercmp("1:1.0-1", "2:0.1-1"));
+    }
+}
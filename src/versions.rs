@@ -0,0 +1,162 @@
+use crate::archive::{self, EntryConsumer, SendError};
+use crate::lazy::{desc_field, MissingDescFieldError};
+use crate::{metrics, Inner, Progress, RepositoryBuilder, Stage};
+use std::collections::HashMap;
+use std::error::Error;
+
+/// Package name, version and file name, the only fields read by
+/// [`RepositoryBuilder::load_versions`]
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct PackageVersion {
+    /// package name
+    pub name: String,
+    /// package version, like `1.0-1`
+    pub version: String,
+    /// package file name, like `foo-1.0-1-x86_64.pkg.tar.zst`
+    pub file_name: String,
+}
+
+impl PackageVersion {
+    fn new(path: &str, desc: &str) -> Result<Self, MissingDescFieldError> {
+        let value = |field| {
+            desc_field(desc, field)
+                .map(str::to_owned)
+                .ok_or_else(|| MissingDescFieldError::new(path, field))
+        };
+        Ok(PackageVersion {
+            name: value("NAME")?,
+            version: value("VERSION")?,
+            file_name: value("FILENAME")?,
+        })
+    }
+}
+
+/// Reads only name, version and file name of each `desc` entry
+struct VersionDescs {
+    packages: Vec<PackageVersion>,
+}
+
+impl EntryConsumer for VersionDescs {
+    type Output = Vec<PackageVersion>;
+
+    fn consume(&mut self, path: String, contents: String) -> Result<(), SendError> {
+        self.packages.push(PackageVersion::new(&path, &contents)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Self::Output, SendError> {
+        Ok(self.packages)
+    }
+}
+
+/// Names and versions of repository packages, see [`RepositoryBuilder::load_versions`]
+pub struct VersionIndex {
+    name: String,
+    url: String,
+    packages: Vec<PackageVersion>,
+    /// Package indexes by names
+    index: HashMap<String, usize>,
+}
+
+impl VersionIndex {
+    fn new(name: String, url: String, packages: Vec<PackageVersion>) -> Self {
+        let index = packages
+            .iter()
+            .enumerate()
+            .map(|(i, package)| (package.name.clone(), i))
+            .collect();
+        VersionIndex {
+            name,
+            url,
+            packages,
+            index,
+        }
+    }
+
+    /// Repository name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Repository url
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Number of packages
+    pub fn len(&self) -> usize {
+        self.packages.len()
+    }
+
+    /// Returns `true` if repository has no packages
+    pub fn is_empty(&self) -> bool {
+        self.packages.is_empty()
+    }
+
+    /// Packages in database order
+    pub fn packages(&self) -> impl Iterator<Item = &PackageVersion> {
+        self.packages.iter()
+    }
+
+    /// Get package by full name
+    pub fn get(&self, name: &str) -> Option<&PackageVersion> {
+        self.index.get(name).map(|&i| &self.packages[i])
+    }
+
+    /// Version of package by full name
+    pub fn version(&self, name: &str) -> Option<&str> {
+        self.get(name).map(|p| p.version.as_str())
+    }
+
+    /// Returns `true` if repository has package with this name
+    pub fn contains(&self, name: &str) -> bool {
+        self.index.contains_key(name)
+    }
+}
+
+impl RepositoryBuilder {
+    /// Download repository database and read only `%NAME%`, `%VERSION%` and `%FILENAME%` of
+    /// each package. It is much faster and uses less memory than full loading, so it suits
+    /// existence and version checks. Last of duplicated names wins. Files metadata, VCS base
+    /// packages, indexes and validation settings are ignored
+    ///
+    /// # Example
+    /// ```ignore
+    /// use archlinux_repo::RepositoryBuilder;
+    ///
+    /// let repo = RepositoryBuilder::new("core", "https://geo.mirror.pkgbuild.com/$repo/os/$arch")
+    ///                 .arch("x86_64")
+    ///                 .load_versions()
+    ///                 .await?;
+    /// println!("{:?}", repo.version("pacman"));
+    /// ```
+    pub async fn load_versions(self) -> Result<VersionIndex, Box<dyn Error>> {
+        let url = self.resolved_url()?;
+        let client = self.client()?;
+        let listener = self.progress_listener.as_ref();
+        let events = &self.options;
+        let progress = |p: Progress| {
+            events.event(|e| e.on_progress(&p));
+            if let Some(l) = listener {
+                l(p)
+            }
+        };
+        let db_url = format!("{}/{}", url, self.options.db_file(&self.name));
+        progress(Progress::new(Stage::LoadingDb));
+        self.options.download_started(&db_url);
+        let response = Inner::request(client.as_ref(), &db_url, &self.options).await?;
+        let packages = archive::read_archive(
+            metrics::observe(response, &self.options, &db_url),
+            (Stage::LoadingDb, Stage::ReadingDb),
+            &["/desc"],
+            &self.options,
+            VersionDescs {
+                packages: Vec::new(),
+            },
+            &progress,
+        )
+        .await?;
+        progress(Progress::new(Stage::DbDone));
+        Ok(VersionIndex::new(self.name, url, packages))
+    }
+}
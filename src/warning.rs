@@ -0,0 +1,106 @@
+use crate::DuplicateKind;
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+
+/// Non-fatal problem found while loading repository. Serialized with `warning` tag, like
+/// `{"warning": "duplicate", "kind": "name", ...}`
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "warning", rename_all = "snake_case")]
+pub enum LoadWarning {
+    /// Several packages have same name, only one of them is registered under it
+    Duplicate {
+        kind: DuplicateKind,
+        /// duplicated name
+        key: String,
+        /// `name-version` of registered package
+        kept: String,
+        /// `name-version` of ignored package
+        ignored: String,
+    },
+    /// Package has invalid `%BUILDDATE%`, unix epoch is used instead, see
+    /// [`crate::RepositoryBuilder::tolerate_invalid_dates`]
+    InvalidBuildDate {
+        /// database entry of package, like `foo-1.0-1`
+        entry: String,
+        /// invalid build date
+        value: String,
+    },
+    /// `desc` entry has field which is not written by `repo-add`, it is ignored
+    UnknownField {
+        /// database entry of package, like `foo-1.0-1`
+        entry: String,
+        /// field name without `%`
+        field: String,
+    },
+    /// Database entry directory doesn't match package name and version
+    EntryMismatch {
+        /// database entry, like `foo-1.0-1`
+        entry: String,
+        /// `name-version` of package from entry
+        package: String,
+    },
+    /// Files database has entry without matching package in main database, usually because
+    /// mirror is syncing. Entry is skipped
+    UnmatchedFiles {
+        /// files database entry, like `foo-1.0-1`
+        entry: String,
+    },
+}
+
+impl Display for LoadWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadWarning::Duplicate {
+                kind,
+                key,
+                kept,
+                ignored,
+            } => write!(
+                f,
+                "Found several packages with {} {}: using {}, ignoring {}",
+                kind, key, kept, ignored
+            ),
+            LoadWarning::InvalidBuildDate { entry, value } => write!(
+                f,
+                "Package {} has invalid build date {}, using unix epoch",
+                entry, value
+            ),
+            LoadWarning::UnknownField { entry, field } => {
+                write!(f, "Package {} has unknown field %{}%", entry, field)
+            }
+            LoadWarning::EntryMismatch { entry, package } => {
+                write!(f, "Database entry {} contains package {}", entry, package)
+            }
+            LoadWarning::UnmatchedFiles { entry } => write!(
+                f,
+                "Files entry {} has no matching package, skipping it",
+                entry
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{DuplicateKind, LoadWarning};
+
+    #[test]
+    fn warning_is_serialized() {
+        let warning = LoadWarning::Duplicate {
+            kind: DuplicateKind::Name,
+            key: "foo".to_owned(),
+            kept: "foo-1.0-1".to_owned(),
+            ignored: "foo-0.9-1".to_owned(),
+        };
+        assert_eq!(
+            serde_json::json!({
+                "warning": "duplicate",
+                "kind": "name",
+                "key": "foo",
+                "kept": "foo-1.0-1",
+                "ignored": "foo-0.9-1",
+            }),
+            serde_json::to_value(&warning).unwrap()
+        );
+    }
+}
@@ -0,0 +1,313 @@
+use crate::{Dependency, HttpClient, HttpError, Package};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// archlinux.org package search url
+pub const PACKAGE_SEARCH_URL: &str = "https://archlinux.org/packages/search/json/";
+
+/// Package from archlinux.org packages web API. Field names match JSON ones
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Default)]
+pub struct WebPackage {
+    pub pkgname: String,
+    pub pkgbase: String,
+    pub repo: String,
+    pub arch: String,
+    pub pkgver: String,
+    pub pkgrel: String,
+    pub epoch: u32,
+    pub pkgdesc: String,
+    pub url: Option<String>,
+    pub filename: String,
+    pub compressed_size: u64,
+    pub installed_size: u64,
+    /// RFC 3339 timestamp
+    pub build_date: String,
+    /// RFC 3339 timestamp of last package update on archlinux.org
+    #[serde(default)]
+    pub last_update: Option<String>,
+    /// RFC 3339 timestamp when package was flagged out-of-date
+    #[serde(default)]
+    pub flag_date: Option<String>,
+    #[serde(default)]
+    pub maintainers: Vec<String>,
+    pub packager: String,
+    #[serde(default)]
+    pub groups: Vec<String>,
+    #[serde(default)]
+    pub licenses: Vec<String>,
+    #[serde(default)]
+    pub conflicts: Vec<String>,
+    #[serde(default)]
+    pub provides: Vec<String>,
+    #[serde(default)]
+    pub replaces: Vec<String>,
+    #[serde(default)]
+    pub depends: Vec<String>,
+    #[serde(default)]
+    pub optdepends: Vec<String>,
+    #[serde(default)]
+    pub makedepends: Vec<String>,
+    #[serde(default)]
+    pub checkdepends: Vec<String>,
+}
+
+impl WebPackage {
+    /// Convert repository package. Web API only fields, like maintainers, are left empty
+    pub fn from_package(package: &Package, repo: &str) -> Self {
+        let (epoch, version) = match package.version.split_once(':') {
+            Some((epoch, version)) => (epoch.parse().unwrap_or(0), version),
+            None => (0, package.version.as_str()),
+        };
+        let (pkgver, pkgrel) = version.rsplit_once('-').unwrap_or((version, ""));
+        let strings = |values: &[String]| values.to_vec();
+        let shared = |values: &[Arc<str>]| values.iter().map(|v| v.to_string()).collect();
+        let dependencies = |values: &[Dependency]| values.iter().map(|d| d.to_string()).collect();
+        WebPackage {
+            pkgname: package.name.clone(),
+            pkgbase: package.base.clone().unwrap_or_else(|| package.name.clone()),
+            repo: repo.to_owned(),
+            arch: package.architecture.to_string(),
+            pkgver: pkgver.to_owned(),
+            pkgrel: pkgrel.to_owned(),
+            epoch,
+            pkgdesc: package.description.clone().unwrap_or_default(),
+            url: package.home_url.clone(),
+            filename: package.file_name.clone(),
+            compressed_size: package.compressed_size,
+            installed_size: package.installed_size,
+            build_date: package
+                .build_date
+                .to_rfc3339_opts(SecondsFormat::Secs, true),
+            last_update: None,
+            flag_date: None,
+            maintainers: Vec::new(),
+            packager: package.packager.to_string(),
+            groups: shared(package.groups()),
+            licenses: shared(package.licenses()),
+            conflicts: strings(package.conflicts()),
+            provides: strings(package.provides()),
+            replaces: strings(package.replaces()),
+            depends: dependencies(package.depends()),
+            optdepends: dependencies(package.optdepends()),
+            makedepends: dependencies(package.makedepends()),
+            checkdepends: dependencies(package.checkdepends()),
+        }
+    }
+
+    /// Full version, like `1:2.0-1`
+    pub fn version(&self) -> String {
+        let mut version = self.pkgver.clone();
+        if self.epoch > 0 {
+            version = format!("{}:{}", self.epoch, version);
+        }
+        if !self.pkgrel.is_empty() {
+            version = format!("{}-{}", version, self.pkgrel);
+        }
+        version
+    }
+
+    /// Convert to repository package. Web API doesn't report checksums and signatures, so
+    /// they are left empty. Fails if build date or dependency is invalid
+    pub fn to_package(&self) -> Result<Package, Box<dyn Error>> {
+        let shared = |values: &[String]| {
+            Some(values.iter().map(|v| Arc::from(v.as_str())).collect())
+                .filter(|v: &Vec<_>| !v.is_empty())
+        };
+        let strings = |values: &[String]| Some(values.to_vec()).filter(|v| !v.is_empty());
+        let dependencies = |values: &[String]| -> Result<Option<Vec<Dependency>>, Box<dyn Error>> {
+            let dependencies = values
+                .iter()
+                .map(|d| Dependency::from_str(d))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Some(dependencies).filter(|d| !d.is_empty()))
+        };
+        Ok(Package {
+            file_name: self.filename.clone(),
+            name: self.pkgname.clone(),
+            base: Some(self.pkgbase.clone()).filter(|b| !b.is_empty()),
+            version: self.version(),
+            description: Some(self.pkgdesc.clone()).filter(|d| !d.is_empty()),
+            groups: shared(&self.groups),
+            compressed_size: self.compressed_size,
+            installed_size: self.installed_size,
+            md5_sum: String::new(),
+            sha256_sum: String::new(),
+            pgp_signature: String::new(),
+            home_url: self.url.clone(),
+            license: shared(&self.licenses),
+            architecture: Arc::from(self.arch.as_str()),
+            build_date: DateTime::parse_from_rfc3339(&self.build_date)?.with_timezone(&Utc),
+            packager: Arc::from(self.packager.as_str()),
+            replaces: strings(&self.replaces),
+            conflicts: strings(&self.conflicts),
+            provides: strings(&self.provides),
+            depends: dependencies(&self.depends)?,
+            optdepends: dependencies(&self.optdepends)?,
+            makedepends: dependencies(&self.makedepends)?,
+            checkdepends: dependencies(&self.checkdepends)?,
+            deltas: None,
+            linked_sources: Vec::new(),
+            raw_desc: None,
+        })
+    }
+}
+
+/// Page of package search results
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Default)]
+pub struct WebSearchResults {
+    pub results: Vec<WebPackage>,
+    /// page number, starting from 1
+    #[serde(default)]
+    pub page: u32,
+    #[serde(default)]
+    pub num_pages: u32,
+    /// maximum number of results per page
+    #[serde(default)]
+    pub limit: u32,
+}
+
+/// Query of archlinux.org package search
+///
+/// # Example
+/// ```ignore
+/// use archlinux_repo::{ReqwestClient, WebSearch};
+///
+/// let results = WebSearch::new()
+///                 .name("pacman")
+///                 .repo("Core")
+///                 .fetch(&ReqwestClient::default())
+///                 .await?;
+/// let package = results.results[0].to_package()?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct WebSearch {
+    url: String,
+    parameters: Vec<(&'static str, String)>,
+}
+
+impl Default for WebSearch {
+    fn default() -> Self {
+        WebSearch::new()
+    }
+}
+
+impl WebSearch {
+    /// Create query to [`PACKAGE_SEARCH_URL`]
+    pub fn new() -> Self {
+        WebSearch {
+            url: PACKAGE_SEARCH_URL.to_owned(),
+            parameters: Vec::new(),
+        }
+    }
+
+    /// Set search url
+    pub fn url(mut self, url: &str) -> Self {
+        self.url = url.to_owned();
+        self
+    }
+
+    /// Search packages with exact name
+    pub fn name(self, name: &str) -> Self {
+        self.parameter("name", name)
+    }
+
+    /// Search packages with name or description containing text
+    pub fn text(self, text: &str) -> Self {
+        self.parameter("q", text)
+    }
+
+    /// Search in repository, like `Core` or `Extra`. Can be called multiple times
+    pub fn repo(self, repo: &str) -> Self {
+        self.parameter("repo", repo)
+    }
+
+    /// Search packages for architecture. Can be called multiple times
+    pub fn arch(self, arch: &str) -> Self {
+        self.parameter("arch", arch)
+    }
+
+    /// Search packages maintained by user
+    pub fn maintainer(self, maintainer: &str) -> Self {
+        self.parameter("maintainer", maintainer)
+    }
+
+    /// Request results page, starting from 1
+    pub fn page(self, page: u32) -> Self {
+        self.parameter("page", &page.to_string())
+    }
+
+    fn parameter(mut self, name: &'static str, value: &str) -> Self {
+        self.parameters.push((name, value.to_owned()));
+        self
+    }
+
+    /// Search url with query parameters
+    fn request_url(&self) -> Result<String, Box<dyn Error>> {
+        let mut url = url::Url::parse(&self.url)?;
+        url.query_pairs_mut().extend_pairs(&self.parameters);
+        Ok(url.to_string())
+    }
+
+    /// Send search request
+    pub async fn fetch(&self, client: &dyn HttpClient) -> Result<WebSearchResults, Box<dyn Error>> {
+        let url = self.request_url()?;
+        let response = client.get(&url).await?;
+        if !response.is_success() {
+            return Err(Box::new(
+                HttpError::from_response("GET", &url, response).await,
+            ));
+        }
+        Ok(serde_json::from_slice(&response.bytes().await?)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{WebPackage, WebSearch, WebSearchResults};
+
+    const RESULTS: &str = r#"{"version": 2, "limit": 250, "valid": true, "num_pages": 1,
+        "page": 1, "results": [{"pkgname": "pacman", "pkgbase": "pacman", "repo": "core",
+        "arch": "x86_64", "pkgver": "6.0.2", "pkgrel": "8", "epoch": 1,
+        "pkgdesc": "A library-based package manager", "url": "https://archlinux.org/pacman/",
+        "filename": "pacman-6.0.2-8-x86_64.pkg.tar.zst", "compressed_size": 898132,
+        "installed_size": 4689218, "build_date": "2023-08-05T13:21:50Z",
+        "last_update": "2023-08-06T10:00:00Z", "flag_date": null, "maintainers": ["allan"],
+        "packager": "Allan <allan@archlinux.org>", "groups": ["base-devel"],
+        "licenses": ["GPL"], "conflicts": [], "provides": ["libalpm.so=13-64"],
+        "replaces": [], "depends": ["glibc", "libarchive>=3.6"],
+        "optdepends": ["perl-locale-gettext: translation support in makepkg-template"],
+        "makedepends": [], "checkdepends": ["python"]}]}"#;
+
+    #[test]
+    fn convert_web_package() {
+        let results: WebSearchResults = serde_json::from_str(RESULTS).unwrap();
+        let package = results.results[0].to_package().unwrap();
+        assert_eq!("1:6.0.2-8", package.version);
+        assert_eq!(1691241710, package.build_date.timestamp());
+        assert_eq!("libarchive", &*package.depends()[1].name);
+        assert_eq!(
+            Some("translation support in makepkg-template"),
+            package.optdepends()[0].description.as_deref()
+        );
+
+        let web = WebPackage::from_package(&package, "core");
+        assert_eq!("6.0.2", web.pkgver);
+        assert_eq!("8", web.pkgrel);
+        assert_eq!(1, web.epoch);
+        assert_eq!(results.results[0].depends, web.depends);
+        assert_eq!(results.results[0].optdepends, web.optdepends);
+        assert_eq!(results.results[0].build_date, web.build_date);
+    }
+
+    #[test]
+    fn search_url() {
+        let search = WebSearch::new().name("pacman").repo("Core").repo("Extra");
+        assert_eq!(
+            "https://archlinux.org/packages/search/json/?name=pacman&repo=Core&repo=Extra",
+            search.request_url().unwrap()
+        );
+    }
+}
@@ -0,0 +1,229 @@
+use crate::package::{read_pkginfo, PkgInfoReadError};
+use crate::{Dependency, Package, PackageBuilder};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use md5::Md5;
+use sha2::{Digest, Sha256};
+use std::error::Error;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+type SendError = Box<dyn Error + Send + Sync>;
+
+/// Checksums and size of package file
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct PackageDigest {
+    pub md5_sum: String,
+    pub sha256_sum: String,
+    pub size: u64,
+}
+
+impl PackageDigest {
+    /// Compute checksums and size of data read from reader
+    pub fn compute<R: Read>(mut reader: R) -> std::io::Result<Self> {
+        let mut md5 = Md5::new();
+        let mut sha256 = Sha256::new();
+        let mut size = 0;
+        let mut buffer = vec![0; 64 * 1024];
+        loop {
+            let read = reader.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            md5.update(&buffer[..read]);
+            sha256.update(&buffer[..read]);
+            size += read as u64;
+        }
+        Ok(PackageDigest {
+            md5_sum: format!("{:x}", md5.finalize()),
+            sha256_sum: format!("{:x}", sha256.finalize()),
+            size,
+        })
+    }
+}
+
+/// Progress of [`RepositoryWriter::add_files`]
+#[derive(Clone, Debug)]
+pub struct DigestProgress<'a> {
+    /// package file which was processed
+    pub file: &'a Path,
+    /// number of processed files
+    pub done: usize,
+    pub total: usize,
+}
+
+/// Writes repository database, like `repo-add`
+///
+/// # Example
+/// ```ignore
+/// use archlinux_repo::RepositoryWriter;
+///
+/// let mut writer = RepositoryWriter::new();
+/// writer.add_files(&["foo-1.0-1-x86_64.pkg.tar.zst"], |p| println!("{}/{}", p.done, p.total))?;
+/// writer.write(std::fs::File::create("custom.db.tar.gz")?)?;
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct RepositoryWriter {
+    packages: Vec<Package>,
+}
+
+impl RepositoryWriter {
+    /// Create writer without packages
+    pub fn new() -> Self {
+        RepositoryWriter::default()
+    }
+
+    /// Packages in the order they were added
+    pub fn packages(&self) -> &[Package] {
+        &self.packages
+    }
+
+    /// Add package, replacing package with the same name
+    pub fn add(&mut self, package: Package) {
+        match self.packages.iter_mut().find(|p| p.name == package.name) {
+            Some(existing) => *existing = package,
+            None => self.packages.push(package),
+        }
+    }
+
+    /// Add package files. Checksums and sizes are computed and `.PKGINFO` is read in parallel
+    /// with `parallel` feature. Progress is called after every file, possibly from several
+    /// threads. Packages are added in file order once all files are read
+    pub fn add_files<P, F>(&mut self, files: &[P], progress: F) -> Result<(), Box<dyn Error>>
+    where
+        P: AsRef<Path> + Sync,
+        F: Fn(DigestProgress) + Sync,
+    {
+        let done = AtomicUsize::new(0);
+        let read = |file: &P| -> Result<Package, SendError> {
+            let file = file.as_ref();
+            let package = read_package_file(file)?;
+            progress(DigestProgress {
+                file,
+                done: done.fetch_add(1, Ordering::SeqCst) + 1,
+                total: files.len(),
+            });
+            Ok(package)
+        };
+        for package in read_all(files, read) {
+            self.add(package.map_err(|e| e as Box<dyn Error>)?);
+        }
+        Ok(())
+    }
+
+    /// Write gzipped database with `name-version/desc` entry for every package
+    pub fn write<W: Write>(&self, writer: W) -> Result<(), Box<dyn Error>> {
+        let mut builder = tar::Builder::new(GzEncoder::new(writer, Compression::default()));
+        for package in &self.packages {
+            let desc = package.to_desc_string()?;
+            let mut header = tar::Header::new_gnu();
+            header.set_size(desc.len() as u64);
+            header.set_mode(0o644);
+            header.set_mtime(package.build_date.timestamp().max(0) as u64);
+            header.set_cksum();
+            let path = format!("{}-{}/desc", package.name, package.version);
+            builder.append_data(&mut header, path, desc.as_bytes())?;
+        }
+        builder.into_inner()?.finish()?.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "parallel")]
+fn read_all<P, F>(files: &[P], read: F) -> Vec<Result<Package, SendError>>
+where
+    P: Sync,
+    F: Fn(&P) -> Result<Package, SendError> + Send + Sync,
+{
+    use rayon::prelude::*;
+    files.par_iter().map(read).collect()
+}
+
+#[cfg(not(feature = "parallel"))]
+fn read_all<P, F>(files: &[P], read: F) -> Vec<Result<Package, SendError>>
+where
+    F: Fn(&P) -> Result<Package, SendError>,
+{
+    files.iter().map(read).collect()
+}
+
+/// Create package from file checksums and its `.PKGINFO`
+fn read_package_file(file: &Path) -> Result<Package, SendError> {
+    let digest = PackageDigest::compute(std::fs::File::open(file)?)?;
+    let info = match read_pkginfo(std::fs::File::open(file)?) {
+        Ok(Some(info)) => info,
+        Ok(None) => return Err(format!("{}: .PKGINFO not found in package", file.display()).into()),
+        Err(PkgInfoReadError::Io(e)) => return Err(Box::new(e)),
+        Err(PkgInfoReadError::Parse(e)) => return Err(Box::new(e)),
+    };
+    let file_name = file
+        .file_name()
+        .map(PathBuf::from)
+        .ok_or_else(|| format!("{} is not a file", file.display()))?;
+    let architecture = info.architecture.as_deref().unwrap_or("any");
+    let mut builder = PackageBuilder::new(&info.name, &info.version, architecture)
+        .file_name(&file_name.to_string_lossy())
+        .compressed_size(digest.size)
+        .installed_size(info.size.unwrap_or(0))
+        .md5_sum(&digest.md5_sum)
+        .sha256_sum(&digest.sha256_sum);
+    if let Some(base) = &info.base {
+        builder = builder.base(base);
+    }
+    if let Some(description) = &info.description {
+        builder = builder.description(description);
+    }
+    if let Some(url) = &info.url {
+        builder = builder.home_url(url);
+    }
+    if let Some(date) = info.build_date {
+        builder = builder.build_date(date);
+    }
+    if let Some(packager) = &info.packager {
+        builder = builder.packager(packager);
+    }
+    builder = info.licenses.iter().fold(builder, |b, v| b.license(v));
+    builder = info.groups.iter().fold(builder, |b, v| b.group(v));
+    builder = info.replaces.iter().fold(builder, |b, v| b.replaces(v));
+    builder = info.conflicts.iter().fold(builder, |b, v| b.conflicts(v));
+    builder = info.provides.iter().fold(builder, |b, v| b.provides(v));
+    builder = info
+        .depends
+        .iter()
+        .cloned()
+        .fold(builder, |b, v| b.depends(v));
+    for optdepend in &info.optdepends {
+        builder = builder.optdepends(Dependency::from_str(optdepend)?);
+    }
+    builder = info
+        .makedepends
+        .iter()
+        .cloned()
+        .fold(builder, |b, v| b.makedepends(v));
+    builder = info
+        .checkdepends
+        .iter()
+        .cloned()
+        .fold(builder, |b, v| b.checkdepends(v));
+    Ok(builder
+        .build()
+        .map_err(|e| format!("{}: {}", file.display(), e))?)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::PackageDigest;
+
+    #[test]
+    fn digest_is_computed() {
+        let digest = PackageDigest::compute(&b""[..]).unwrap();
+        assert_eq!("d41d8cd98f00b204e9800998ecf8427e", digest.md5_sum);
+        assert_eq!(
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            digest.sha256_sum
+        );
+        assert_eq!(0, digest.size);
+    }
+}